@@ -1,30 +1,444 @@
+mod cast;
 mod simulator;
 
+use cast::CastComparison;
 use eframe::egui::{ComboBox, Context};
 use eframe::{
     egui::{self},
     Frame,
 };
 use include_bytes_zstd::include_bytes_zstd;
-use simulator::{Character, Viewer};
+#[cfg(not(target_arch = "wasm32"))]
+use simulator::GifFrame;
+use simulator::{Bookmark, BoxColors, Character, SavedView, Viewer, CHARACTERS};
+use std::collections::{HashMap, VecDeque};
+
+/// How many opened files the Recent menu remembers.
+#[cfg(not(target_arch = "wasm32"))]
+const RECENT_FILE_COUNT: usize = 8;
+
+/// What gets remembered between launches.
+#[derive(serde::Deserialize, serde::Serialize)]
+struct SavedState {
+    character_name: String,
+    selected_index: i32,
+    current_frame: usize,
+    #[serde(default)]
+    box_colors: BoxColors,
+    #[serde(default)]
+    recent_files: Vec<String>,
+    #[serde(default = "default_origin")]
+    default_origin: [f32; 2],
+    #[serde(default)]
+    views: HashMap<String, SavedView>,
+    #[serde(default)]
+    bookmarks: Vec<Bookmark>,
+    #[serde(default)]
+    favorites: HashMap<String, Vec<i32>>,
+    #[serde(default)]
+    box_rounding: f32,
+    #[serde(default = "default_box_stroke_width")]
+    box_stroke_width: f32,
+    /// None keeps egui's default theme.
+    #[serde(default)]
+    dark_mode: Option<bool>,
+    #[serde(default)]
+    show_performance: bool,
+}
+
+fn default_origin() -> [f32; 2] {
+    Viewer::default().default_origin
+}
+
+fn default_box_stroke_width() -> f32 {
+    Viewer::default().box_stroke_width
+}
+
+/// The decompressed fchar of a bundled character, by `CharacterInfo::file_id`.
+fn bundled_file(file_id: u32) -> Option<Vec<u8>> {
+    // The files have to be embedded one by one, but everything else about a character
+    // comes from the CHARACTERS table.
+    match file_id {
+        0 => Some(include_bytes_zstd!("assets/000.fchar.17", 9)),
+        1 => Some(include_bytes_zstd!("assets/001.fchar.17", 9)),
+        2 => Some(include_bytes_zstd!("assets/002.fchar.17", 9)),
+        3 => Some(include_bytes_zstd!("assets/003.fchar.17", 9)),
+        4 => Some(include_bytes_zstd!("assets/004.fchar.17", 9)),
+        5 => Some(include_bytes_zstd!("assets/005.fchar.17", 9)),
+        6 => Some(include_bytes_zstd!("assets/006.fchar.17", 9)),
+        7 => Some(include_bytes_zstd!("assets/007.fchar.17", 9)),
+        8 => Some(include_bytes_zstd!("assets/008.fchar.17", 9)),
+        9 => Some(include_bytes_zstd!("assets/009.fchar.17", 9)),
+        10 => Some(include_bytes_zstd!("assets/010.fchar.17", 9)),
+        11 => Some(include_bytes_zstd!("assets/011.fchar.17", 9)),
+        12 => Some(include_bytes_zstd!("assets/012.fchar.17", 9)),
+        15 => Some(include_bytes_zstd!("assets/015.fchar.17", 9)),
+        16 => Some(include_bytes_zstd!("assets/016.fchar.17", 9)),
+        17 => Some(include_bytes_zstd!("assets/017.fchar.17", 9)),
+        18 => Some(include_bytes_zstd!("assets/018.fchar.17", 9)),
+        20 => Some(include_bytes_zstd!("assets/020.fchar.17", 9)),
+        21 => Some(include_bytes_zstd!("assets/021.fchar.17", 9)),
+        _ => None,
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_png(image: &egui::ColorImage, file_name: &str) {
+    let path = rfd::FileDialog::new()
+        .add_filter("PNG", &["png"])
+        .set_file_name(file_name)
+        .save_file();
+    let path = match path {
+        Some(path) => path,
+        None => return,
+    };
+    if let Err(err) = image::save_buffer(
+        &path,
+        image.as_raw(),
+        image.width() as u32,
+        image.height() as u32,
+        image::ColorType::Rgba8,
+    ) {
+        log::error!("Failed to write {}: {}", path.display(), err);
+    }
+}
+
+/// Encodes the captured frames as a looping GIF. Frames that don't match the size of the
+/// first one, because the window was resized during the export, are left out.
+#[cfg(not(target_arch = "wasm32"))]
+fn write_gif(
+    path: &std::path::Path,
+    gif_frames: &[egui::ColorImage],
+    fps: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let size = match gif_frames.first() {
+        Some(first) => first.size,
+        None => return Ok(()),
+    };
+    let file = std::fs::File::create(path)?;
+    let mut encoder = gif::Encoder::new(file, size[0] as u16, size[1] as u16, &[])?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+    // GIF delays are in hundredths of a second, and most viewers slow down anything under 2.
+    let delay = (100 / fps.max(1)).max(2) as u16;
+    for image in gif_frames {
+        if image.size != size {
+            continue;
+        }
+        let mut pixels = image.as_raw().to_vec();
+        let mut gif_frame =
+            gif::Frame::from_rgba_speed(size[0] as u16, size[1] as u16, &mut pixels, 10);
+        gif_frame.delay = delay;
+        encoder.write_frame(&gif_frame)?;
+    }
+    Ok(())
+}
+
+/// Asks where to save the GIF, then encodes it on its own thread. Encoding takes a while for a
+/// long action, and doing it here would freeze the window until it's done.
+#[cfg(not(target_arch = "wasm32"))]
+fn save_gif(gif_frames: Vec<egui::ColorImage>, fps: u32, file_name: &str) {
+    let path = rfd::FileDialog::new()
+        .add_filter("GIF", &["gif"])
+        .set_file_name(file_name)
+        .save_file();
+    let path = match path {
+        Some(path) => path,
+        None => return,
+    };
+    std::thread::spawn(move || {
+        if let Err(err) = write_gif(&path, &gif_frames, fps) {
+            log::error!("Failed to write {}: {}", path.display(), err);
+        }
+    });
+}
 
 #[derive(Default)]
 pub struct SF6Simulator {
     viewer: Viewer,
     character_name: String,
+    /// Paths of the most recently opened files, newest first.
+    recent_files: Vec<String>,
+    /// The theme picked with the radio buttons or F2, remembered for the next launch.
+    dark_mode: Option<bool>,
+    /// Canvas rect and file name of a PNG export waiting for its screenshot.
+    #[cfg(not(target_arch = "wasm32"))]
+    pending_png: Option<(egui::Rect, String)>,
+    /// The GIF frame drawn this update, waiting for its screenshot.
+    #[cfg(not(target_arch = "wasm32"))]
+    pending_gif_frame: Option<GifFrame>,
+    /// Frames of the GIF export captured so far.
+    #[cfg(not(target_arch = "wasm32"))]
+    gif_frames: Vec<egui::ColorImage>,
+    /// The window comparing one action across every character.
+    cast_comparison: CastComparison,
+    /// Whether the repaint rate and update time are shown in the corner. F3 toggles it.
+    show_performance: bool,
+    /// When each update of the last second ran, in egui's input time.
+    update_times: VecDeque<f64>,
 }
 
 impl SF6Simulator {
-    pub(crate) fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        Self {
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let mut app = Self {
             viewer: Default::default(),
             character_name: "Select a character".to_string(),
+            recent_files: vec![],
+            dark_mode: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            pending_png: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            pending_gif_frame: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            gif_frames: vec![],
+            cast_comparison: Default::default(),
+            show_performance: false,
+            update_times: VecDeque::new(),
+        };
+        if let Some(storage) = cc.storage {
+            if let Some(state) = eframe::get_value::<SavedState>(storage, eframe::APP_KEY) {
+                app.viewer.box_colors = state.box_colors;
+                app.recent_files = state.recent_files;
+                app.viewer.default_origin = state.default_origin;
+                app.viewer.views = state.views;
+                app.viewer.bookmarks = state.bookmarks;
+                app.viewer.favorites = state.favorites;
+                app.viewer.box_rounding = state.box_rounding;
+                app.viewer.box_stroke_width = state.box_stroke_width;
+                app.show_performance = state.show_performance;
+                match state.dark_mode {
+                    Some(dark_mode) => {
+                        app.dark_mode = Some(dark_mode);
+                        cc.egui_ctx.set_visuals(if dark_mode {
+                            egui::Visuals::dark()
+                        } else {
+                            egui::Visuals::light()
+                        });
+                    }
+                    None => (),
+                }
+                if app.select_character(&state.character_name) {
+                    app.viewer
+                        .restore_selection(state.selected_index, state.current_frame);
+                }
+            }
+        }
+        app
+    }
+
+    /// The repaint rate over the last second and the time the last update took, in the bottom
+    /// left corner. The window only repaints on input or while something is moving, so the rate
+    /// should drop to about 1 when nothing happens; the overlay itself repaints once a second to
+    /// keep the numbers current.
+    fn render_performance(&mut self, ctx: &Context, frame: &Frame) {
+        let now = ctx.input(|i| i.time);
+        self.update_times.push_back(now);
+        while let Some(oldest) = self.update_times.front() {
+            if now - oldest <= 1.0 {
+                break;
+            }
+            self.update_times.pop_front();
+        }
+        ctx.request_repaint_after(std::time::Duration::from_secs(1));
+        egui::Area::new("performance")
+            .anchor(egui::Align2::LEFT_BOTTOM, egui::vec2(8.0, -8.0))
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label(format!("{} repaints/s", self.update_times.len()));
+                    match frame.info().cpu_usage {
+                        Some(cpu_usage) => {
+                            ui.label(format!("{:.2} ms per update", cpu_usage * 1000.0));
+                        }
+                        None => (),
+                    }
+                });
+            });
+    }
+
+    /// Opens one of the bundled characters by its display name.
+    fn select_character(&mut self, name: &str) -> bool {
+        let info = match CHARACTERS.iter().find(|info| info.name == name) {
+            Some(info) => info,
+            None => return false,
+        };
+        let buffer = match bundled_file(info.file_id) {
+            Some(buffer) => buffer,
+            None => return false,
+        };
+        self.viewer.remember_view();
+        match self.viewer.open_fchar(buffer) {
+            Ok(()) => self.viewer.load_error = None,
+            Err(err) => {
+                self.viewer.load_error = Some(format!("Failed to load {}: {}", name, err));
+                return false;
+            }
+        }
+        self.character_name = name.to_string();
+        self.viewer.character = info.character;
+        self.viewer.character_name = self.character_name.clone();
+        self.viewer.recall_view();
+        true
+    }
+
+    /// Opens the character an undo or redo went back to: a built-in one, or a file that's
+    /// still in the Recent menu.
+    fn open_history_character(&mut self, name: &str) -> bool {
+        if self.select_character(name) {
+            return true;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let path = self.recent_files.iter().find(|path| {
+                std::path::Path::new(path)
+                    .file_name()
+                    .is_some_and(|file_name| file_name.to_string_lossy() == name)
+            });
+            match path.cloned() {
+                Some(path) => {
+                    self.open_path(std::path::PathBuf::from(path));
+                    return self.character_name == name;
+                }
+                None => (),
+            }
+        }
+        self.viewer.load_error = Some(format!("Can't go back to {}, it isn't open anymore.", name));
+        false
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_file(&mut self) {
+        let path = rfd::FileDialog::new()
+            .add_filter("fchar", &["17"])
+            .pick_file();
+        match path {
+            Some(path) => self.open_path(path),
+            None => (),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_comparison(&mut self) {
+        let path = rfd::FileDialog::new()
+            .add_filter("fchar", &["17"])
+            .pick_file();
+        let path = match path {
+            Some(path) => path,
+            None => return,
+        };
+        let name = match path.file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => path.display().to_string(),
+        };
+        let result = match std::fs::read(&path) {
+            Ok(buffer) => self.viewer.open_comparison(buffer, name.clone()),
+            Err(err) => Err(err.to_string()),
+        };
+        self.viewer.load_error = match result {
+            Ok(()) => None,
+            Err(err) => Some(format!("Failed to load {}: {}", name, err)),
+        };
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_path(&mut self, path: std::path::PathBuf) {
+        let name = match path.file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => path.display().to_string(),
+        };
+        self.viewer.remember_view();
+        let result = match std::fs::read(&path) {
+            Ok(buffer) => self.viewer.open_fchar(buffer),
+            Err(err) => Err(err.to_string()),
+        };
+        match result {
+            Ok(()) => {
+                // Files keep the game's numbering, which tells which character's action names
+                // to use. Anything else shows plain action IDs.
+                self.viewer.character = match Character::from_file_name(&name) {
+                    Some(info) => info.character,
+                    None => Character::Unknown,
+                };
+                self.viewer.load_error = None;
+                self.character_name = name;
+                self.viewer.character_name = self.character_name.clone();
+                self.viewer.recall_view();
+                let path = path.display().to_string();
+                self.recent_files.retain(|recent| *recent != path);
+                self.recent_files.insert(0, path);
+                self.recent_files.truncate(RECENT_FILE_COUNT);
+            }
+            Err(err) => {
+                log::error!("Failed to load {}: {}", path.display(), err);
+                self.viewer.load_error = Some(format!("Failed to load {}: {}", name, err));
+            }
         }
     }
 }
 
 impl eframe::App for SF6Simulator {
-    fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        self.viewer.remember_view();
+        let state = SavedState {
+            character_name: self.character_name.clone(),
+            selected_index: self.viewer.selected_index(),
+            current_frame: self.viewer.current_frame(),
+            box_colors: self.viewer.box_colors.clone(),
+            recent_files: self.recent_files.clone(),
+            default_origin: self.viewer.default_origin,
+            views: self.viewer.views.clone(),
+            bookmarks: self.viewer.bookmarks.clone(),
+            favorites: self.viewer.favorites.clone(),
+            box_rounding: self.viewer.box_rounding,
+            box_stroke_width: self.viewer.box_stroke_width,
+            dark_mode: self.dark_mode,
+            show_performance: self.show_performance,
+        };
+        eframe::set_value(storage, eframe::APP_KEY, &state);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn post_rendering(&mut self, _window_size_px: [u32; 2], frame: &Frame) {
+        let screenshot = match frame.screenshot() {
+            Some(screenshot) => screenshot,
+            None => return,
+        };
+        let pixels_per_point = frame.info().native_pixels_per_point;
+        match self.pending_png.take() {
+            Some((rect, file_name)) => {
+                save_png(&screenshot.region(&rect, pixels_per_point), &file_name)
+            }
+            None => (),
+        }
+        match self.pending_gif_frame.take() {
+            Some(gif_frame) => {
+                self.gif_frames
+                    .push(screenshot.region(&gif_frame.rect, pixels_per_point));
+                if gif_frame.last {
+                    let gif_frames = std::mem::take(&mut self.gif_frames);
+                    save_gif(gif_frames, gif_frame.fps, &gif_frame.file_name);
+                }
+            }
+            None => (),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context, frame: &mut Frame) {
+        if ctx.input(|i| i.key_pressed(egui::Key::F2)) {
+            let visuals = if ctx.style().visuals.dark_mode {
+                egui::Visuals::light()
+            } else {
+                egui::Visuals::dark()
+            };
+            self.dark_mode = Some(visuals.dark_mode);
+            ctx.set_visuals(visuals);
+        }
+        if ctx.input(|i| i.key_pressed(egui::Key::F3)) {
+            self.show_performance = !self.show_performance;
+        }
+        if self.show_performance {
+            self.render_performance(ctx, frame);
+        }
+        self.cast_comparison.ui(ctx, self.viewer.action_id());
         egui::SidePanel::right("Motion Info")
             .resizable(true)
             .default_width(400.0)
@@ -40,128 +454,160 @@ impl eframe::App for SF6Simulator {
                     .selected_text(self.character_name.clone())
                     .width(150.0)
                     .show_ui(ui, |ui| {
-                        if ui.selectable_label(true, "Common").clicked() {
-                            self.character_name = "Common".to_string();
-                            self.viewer
-                                .open_fchar(include_bytes_zstd!("assets/000.fchar.17", 9));
-                            self.viewer.character = Character::Common;
-                        }
-                        if ui.selectable_label(true, "Ryu").clicked() {
-                            self.character_name = "Ryu".to_string();
-                            self.viewer
-                                .open_fchar(include_bytes_zstd!("assets/001.fchar.17", 9));
-                            self.viewer.character = Character::Ryu;
-                        }
-                        if ui.selectable_label(true, "Luke").clicked() {
-                            self.character_name = "Luke".to_string();
-                            self.viewer
-                                .open_fchar(include_bytes_zstd!("assets/002.fchar.17", 9));
-                            self.viewer.character = Character::Luke;
-                        }
-                        if ui.selectable_label(true, "Kimberly").clicked() {
-                            self.character_name = "Kimberly".to_string();
-                            self.viewer
-                                .open_fchar(include_bytes_zstd!("assets/003.fchar.17", 9));
-                            self.viewer.character = Character::Kimberly;
-                        }
-                        if ui.selectable_label(true, "Chun-Li").clicked() {
-                            self.character_name = "Chun-Li".to_string();
-                            self.viewer
-                                .open_fchar(include_bytes_zstd!("assets/004.fchar.17", 9));
-                            self.viewer.character = Character::ChunLi;
-                        }
-                        if ui.selectable_label(true, "Manon").clicked() {
-                            self.character_name = "Manon".to_string();
-                            self.viewer
-                                .open_fchar(include_bytes_zstd!("assets/005.fchar.17", 9));
-                            self.viewer.character = Character::Manon;
-                        }
-                        if ui.selectable_label(true, "Zangief").clicked() {
-                            self.character_name = "Zangief".to_string();
-                            self.viewer
-                                .open_fchar(include_bytes_zstd!("assets/006.fchar.17", 9));
-                            self.viewer.character = Character::Zangief;
-                        }
-                        if ui.selectable_label(true, "JP").clicked() {
-                            self.character_name = "JP".to_string();
-                            self.viewer
-                                .open_fchar(include_bytes_zstd!("assets/007.fchar.17", 9));
-                            self.viewer.character = Character::JP;
-                        }
-                        if ui.selectable_label(true, "Dhalsim").clicked() {
-                            self.character_name = "Dhalsim".to_string();
-                            self.viewer
-                                .open_fchar(include_bytes_zstd!("assets/008.fchar.17", 9));
-                            self.viewer.character = Character::Dhalsim;
-                        }
-                        if ui.selectable_label(true, "Cammy").clicked() {
-                            self.character_name = "Cammy".to_string();
-                            self.viewer
-                                .open_fchar(include_bytes_zstd!("assets/009.fchar.17", 9));
-                            self.viewer.character = Character::Cammy;
-                        }
-                        if ui.selectable_label(true, "Ken").clicked() {
-                            self.character_name = "Ken".to_string();
-                            self.viewer
-                                .open_fchar(include_bytes_zstd!("assets/010.fchar.17", 9));
-                            self.viewer.character = Character::Ken;
-                        }
-                        if ui.selectable_label(true, "Dee Jay").clicked() {
-                            self.character_name = "Dee Jay".to_string();
-                            self.viewer
-                                .open_fchar(include_bytes_zstd!("assets/011.fchar.17", 9));
-                            self.viewer.character = Character::DeeJay;
-                        }
-                        if ui.selectable_label(true, "Lily").clicked() {
-                            self.character_name = "Lily".to_string();
-                            self.viewer
-                                .open_fchar(include_bytes_zstd!("assets/012.fchar.17", 9));
-                            self.viewer.character = Character::Lily;
-                        }
-                        if ui.selectable_label(true, "Blanka").clicked() {
-                            self.character_name = "Blanka".to_string();
-                            self.viewer
-                                .open_fchar(include_bytes_zstd!("assets/015.fchar.17", 9));
-                            self.viewer.character = Character::Blanka;
-                        }
-                        if ui.selectable_label(true, "Juri").clicked() {
-                            self.character_name = "Juri".to_string();
-                            self.viewer
-                                .open_fchar(include_bytes_zstd!("assets/016.fchar.17", 9));
-                            self.viewer.character = Character::Juri;
-                        }
-                        if ui.selectable_label(true, "Marisa").clicked() {
-                            self.character_name = "Marisa".to_string();
-                            self.viewer
-                                .open_fchar(include_bytes_zstd!("assets/017.fchar.17", 9));
-                            self.viewer.character = Character::Marisa;
+                        for info in &CHARACTERS {
+                            if ui.selectable_label(true, info.name).clicked() {
+                                self.select_character(info.name);
+                            }
                         }
-                        if ui.selectable_label(true, "Guile").clicked() {
-                            self.character_name = "Guile".to_string();
-                            self.viewer
-                                .open_fchar(include_bytes_zstd!("assets/018.fchar.17", 9));
-                            self.viewer.character = Character::Guile;
+                        #[cfg(not(target_arch = "wasm32"))]
+                        if ui.selectable_label(true, "Open File…").clicked() {
+                            self.open_file();
                         }
-                        if ui.selectable_label(true, "E. Honda").clicked() {
-                            self.character_name = "E. Honda".to_string();
-                            self.viewer
-                                .open_fchar(include_bytes_zstd!("assets/020.fchar.17", 9));
-                            self.viewer.character = Character::EHonda;
+                    });
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.menu_button("Recent", |ui| {
+                    self.recent_files
+                        .retain(|recent| std::path::Path::new(recent).exists());
+                    if self.recent_files.is_empty() {
+                        ui.label("No recent files");
+                    }
+                    let mut opened: Option<String> = None;
+                    for recent in &self.recent_files {
+                        if ui.button(recent).clicked() {
+                            opened = Some(recent.clone());
                         }
-                        if ui.selectable_label(true, "Jamie").clicked() {
-                            self.character_name = "Jamie".to_string();
-                            self.viewer
-                                .open_fchar(include_bytes_zstd!("assets/021.fchar.17", 9));
-                            self.viewer.character = Character::Jamie;
+                    }
+                    match opened {
+                        Some(path) => {
+                            self.open_path(path.into());
+                            ui.close_menu();
                         }
-                    });
+                        None => (),
+                    }
+                });
+                #[cfg(not(target_arch = "wasm32"))]
+                if self.viewer.asset.is_some()
+                    && ui
+                        .button("Compare…")
+                        .on_hover_text(
+                            "Load another version of this character's file and show what \
+                            changed in each action.",
+                        )
+                        .clicked()
+                {
+                    self.open_comparison();
+                }
+                if ui
+                    .button("Cast…")
+                    .on_hover_text(
+                        "Line up one action, such as 5LP, across every character's file.",
+                    )
+                    .clicked()
+                {
+                    self.cast_comparison.show_action(self.viewer.action_id());
+                }
                 let mut visuals = ui.ctx().style().visuals.clone();
-                visuals.light_dark_radio_buttons(ui);
+                let dark_mode = visuals.dark_mode;
+                ui.horizontal(|ui| {
+                    visuals.light_dark_radio_buttons(ui);
+                })
+                .response
+                .on_hover_text("F2 switches between the themes.");
+                if visuals.dark_mode != dark_mode {
+                    self.dark_mode = Some(visuals.dark_mode);
+                }
                 ui.ctx().set_visuals(visuals);
+                ui.checkbox(&mut self.show_performance, "FPS").on_hover_text(
+                    "Show how often the window repaints and how long an update takes. F3 toggles \
+                    it.",
+                );
             });
+            let mut dismissed = false;
+            match &self.viewer.load_error {
+                Some(load_error) => {
+                    ui.horizontal(|ui| {
+                        ui.colored_label(ui.visuals().error_fg_color, load_error);
+                        dismissed = ui.small_button("✖").clicked();
+                    });
+                }
+                None => (),
+            }
+            if dismissed {
+                self.viewer.load_error = None;
+            }
+            if !self.viewer.schema_warnings.is_empty() {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    "⚠ This file's layout doesn't match the one the viewer was written for, so \
+                    boxes may be wrong. It's likely from a newer game version.",
+                )
+                .on_hover_text(self.viewer.schema_warnings.join("\n"));
+            }
             if self.viewer.asset.is_some() {
                 self.viewer.ui(ui);
             }
         });
+        match self.viewer.take_history_character() {
+            Some(name) => {
+                if self.open_history_character(&name) {
+                    self.viewer.apply_history();
+                }
+            }
+            None => (),
+        }
+        // The canvas was drawn above, so the screenshot of this frame has it.
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(pending_png) = self.viewer.take_png_export() {
+            self.pending_png = Some(pending_png);
+            frame.request_screenshot();
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(gif_frame) = self.viewer.take_gif_frame() {
+            self.pending_gif_frame = Some(gif_frame);
+            frame.request_screenshot();
+        }
+        #[cfg(target_arch = "wasm32")]
+        let _ = frame;
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    fn solid_image(size: [usize; 2], color: egui::Color32) -> egui::ColorImage {
+        egui::ColorImage::new(size, color)
+    }
+
+    #[test]
+    fn write_gif_round_trips() {
+        let path = std::env::temp_dir().join(format!("sf6_sim_test_{}.gif", std::process::id()));
+        let gif_frames = vec![
+            solid_image([4, 3], egui::Color32::RED),
+            solid_image([4, 3], egui::Color32::BLUE),
+            // Left out, since the window was resized.
+            solid_image([5, 3], egui::Color32::GREEN),
+        ];
+        write_gif(&path, &gif_frames, 20).unwrap();
+
+        let file = std::fs::File::open(&path).unwrap();
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(file).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (4, 3));
+        let mut colors = vec![];
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.delay, 5);
+            colors.push(frame.buffer[..4].to_vec());
+        }
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(colors, vec![vec![255, 0, 0, 255], vec![0, 0, 255, 255]]);
+    }
+
+    #[test]
+    fn write_gif_without_frames_writes_nothing() {
+        let path = std::env::temp_dir().join(format!("sf6_sim_empty_{}.gif", std::process::id()));
+        write_gif(&path, &[], 20).unwrap();
+        assert!(!path.exists());
     }
 }
@@ -0,0 +1,214 @@
+use super::bundled_file;
+use super::simulator::{ActionSummary, Character, CHARACTERS};
+use crate::extract;
+use eframe::egui::{self, Context};
+use sf6_rsz_parser::fchar::CharacterAsset;
+use std::collections::HashMap;
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum CastColumn {
+    Character,
+    Startup,
+    Active,
+    Recovery,
+    Frames,
+}
+
+/// A window lining up one action ID across every bundled character, such as everyone's 5LP.
+/// Each character's file is parsed on its own update the first time it's needed, so opening
+/// the window doesn't freeze the app. The parsed files are kept for looking up other action
+/// IDs, and the results for every action ID looked at.
+pub struct CastComparison {
+    pub open: bool,
+    action_id: i32,
+    /// Parsed files by character name. None if the file didn't load.
+    assets: HashMap<&'static str, Option<CharacterAsset>>,
+    /// Frame data by character name and action ID. None if the character has no such action or
+    /// its file didn't load.
+    summaries: HashMap<(&'static str, i32), Option<ActionSummary>>,
+    sort_column: CastColumn,
+    descending: bool,
+}
+
+impl Default for CastComparison {
+    fn default() -> Self {
+        Self {
+            open: false,
+            action_id: 0,
+            assets: HashMap::new(),
+            summaries: HashMap::new(),
+            sort_column: CastColumn::Character,
+            descending: false,
+        }
+    }
+}
+
+fn frames_to_string(frames: Option<i32>) -> String {
+    match frames {
+        Some(frames) => frames.to_string(),
+        None => "-".to_string(),
+    }
+}
+
+impl CastComparison {
+    /// Opens the window on `action_id`.
+    pub fn show_action(&mut self, action_id: i32) {
+        self.open = true;
+        if action_id != -1 {
+            self.action_id = action_id;
+        }
+    }
+
+    /// Loads the next character that's missing for the current action ID, and reports how many
+    /// are loaded.
+    fn load_next(&mut self, ctx: &Context) -> usize {
+        let mut loaded = 0;
+        for info in CHARACTERS
+            .iter()
+            .filter(|info| info.character != Character::Common)
+        {
+            if self.summaries.contains_key(&(info.name, self.action_id)) {
+                loaded += 1;
+                continue;
+            }
+            let fchar = self.assets.entry(info.name).or_insert_with(|| {
+                let buffer = bundled_file(info.file_id)?;
+                extract::open(&buffer).ok()
+            });
+            let summary = fchar.as_ref().and_then(|fchar| {
+                ActionSummary::from_action(fchar, info.character, self.action_id)
+            });
+            self.summaries.insert((info.name, self.action_id), summary);
+            ctx.request_repaint();
+            return loaded + 1;
+        }
+        loaded
+    }
+
+    fn sort_key(&self, name: &str, summary: &Option<ActionSummary>) -> (bool, Option<i32>, String) {
+        let value = match summary {
+            Some(summary) => match self.sort_column {
+                CastColumn::Character => None,
+                CastColumn::Startup => summary.startup,
+                CastColumn::Active => summary.active_frames().map(|frames| frames as i32),
+                CastColumn::Recovery => summary.recovery,
+                CastColumn::Frames => Some(summary.frames as i32),
+            },
+            None => None,
+        };
+        // Characters without a value go last whichever way the table is sorted.
+        let missing = self.sort_column != CastColumn::Character && value.is_none();
+        (missing, value, name.to_string())
+    }
+
+    fn header(&mut self, ui: &mut egui::Ui, column: CastColumn, label: &str) {
+        let arrow = match (self.sort_column == column, self.descending) {
+            (true, false) => " ⏶",
+            (true, true) => " ⏷",
+            (false, _) => "",
+        };
+        if ui
+            .selectable_label(self.sort_column == column, format!("{}{}", label, arrow))
+            .clicked()
+        {
+            if self.sort_column == column {
+                self.descending = !self.descending;
+            } else {
+                self.sort_column = column;
+                self.descending = false;
+            }
+        }
+    }
+
+    pub fn ui(&mut self, ctx: &Context, selected_action_id: i32) {
+        let mut open = self.open;
+        egui::Window::new("Compare across the cast")
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Action ID");
+                    ui.add(egui::DragValue::new(&mut self.action_id).clamp_range(0..=9999));
+                    if ui
+                        .add_enabled(
+                            selected_action_id != -1,
+                            egui::Button::new("Use selected action"),
+                        )
+                        .clicked()
+                    {
+                        self.action_id = selected_action_id;
+                    }
+                });
+                let characters = CHARACTERS
+                    .iter()
+                    .filter(|info| info.character != Character::Common)
+                    .count();
+                let loaded = self.load_next(ctx);
+                if loaded < characters {
+                    ui.label(format!("Loading characters… {}/{}", loaded, characters));
+                }
+                let mut rows: Vec<(&'static str, Option<ActionSummary>)> = CHARACTERS
+                    .iter()
+                    .filter_map(|info| {
+                        self.summaries
+                            .get(&(info.name, self.action_id))
+                            .map(|summary| (info.name, summary.clone()))
+                    })
+                    .collect();
+                rows.sort_by(|(a_name, a), (b_name, b)| {
+                    let (a_missing, a_value, a_name) = self.sort_key(a_name, a);
+                    let (b_missing, b_value, b_name) = self.sort_key(b_name, b);
+                    let order = a_value.cmp(&b_value).then(a_name.cmp(&b_name));
+                    a_missing.cmp(&b_missing).then(if self.descending {
+                        order.reverse()
+                    } else {
+                        order
+                    })
+                });
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("cast_comparison")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            self.header(ui, CastColumn::Character, "Character");
+                            ui.label("Action");
+                            self.header(ui, CastColumn::Startup, "Startup");
+                            self.header(ui, CastColumn::Active, "Active");
+                            self.header(ui, CastColumn::Recovery, "Recovery");
+                            self.header(ui, CastColumn::Frames, "Frames");
+                            ui.end_row();
+                            for (name, summary) in &rows {
+                                ui.label(*name);
+                                match summary {
+                                    Some(summary) => {
+                                        ui.label(&summary.name);
+                                        ui.label(frames_to_string(summary.startup));
+                                        let windows: Vec<String> = summary
+                                            .active
+                                            .iter()
+                                            .map(|(start, end)| (end - start + 1).to_string())
+                                            .collect();
+                                        if windows.is_empty() {
+                                            ui.label("-");
+                                        } else {
+                                            ui.label(windows.join(", "));
+                                        }
+                                        ui.label(frames_to_string(summary.recovery));
+                                        ui.label(summary.frames.to_string());
+                                    }
+                                    None => match self.assets.get(name) {
+                                        Some(None) => {
+                                            ui.label("Failed to load");
+                                        }
+                                        _ => {
+                                            ui.label("No such action");
+                                        }
+                                    },
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+        self.open = open;
+    }
+}
@@ -1,130 +1,182 @@
-mod action_names;
+mod box_colors;
+mod diff;
 
-use bitvec::vec::BitVec;
+use crate::extract::action_names::CommonActions;
+use crate::extract::{
+    self, flags_to_string, steer_key_to_value, ActionInfo, AttackCollisionKey, Branch, CancelWindow,
+    CollisionBox, DamageCollisionKey, FrameBoxes, HitData, HitWindow, MissingBox, MotionClip,
+    PersonalData, PushCollisionKey, RawKey, SteerOperationType, SteerValueType, TimelineCell,
+    Trigger, DAMAGE_TYPE_FLAGS, IMMUNE_FLAGS, PUSH_ATTRIBUTE_FLAGS,
+};
 use eframe::egui;
-use eframe::egui::{Color32, ComboBox, Frame, Sense, Slider};
-use eframe::emath::{Pos2, Rect};
+use eframe::egui::{Align2, Color32, ComboBox, FontId, Frame, RichText, Sense, Slider};
+use eframe::emath::{Pos2, Rect, Vec2};
 use eframe::epaint::Stroke;
-use include_bytes_zstd::include_bytes_zstd;
-use num_derive::FromPrimitive;
-use sf6_rsz_parser::fchar::{CharacterAsset, DataId};
-use sf6_rsz_parser::parse_fchar;
-use sf6_rsz_parser::rsz::json_parser::parse_json;
+use sf6_rsz_parser::fchar::CharacterAsset;
 use sf6_rsz_parser::rsz::{Float3, RSZValue};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-#[derive(Default)]
-pub enum Character {
-    #[default]
-    Common,
-    Ryu,
-    Luke,
-    Kimberly,
-    ChunLi,
-    Manon,
-    Zangief,
-    JP,
-    Dhalsim,
-    Cammy,
-    Ken,
-    DeeJay,
-    Lily,
-    Blanka,
-    Juri,
-    Marisa,
-    Guile,
-    EHonda,
-    Jamie,
+use box_colors::{GUARD_REQUIREMENTS, HURTBOX_REGIONS};
+use diff::{ActionDiff, BoxChangeKind};
+pub use box_colors::BoxColors;
+pub use crate::extract::{Character, CHARACTERS};
+
+/// Spacing of the viewport grid, in game units.
+const GRID_SPACING: f32 = 100.0;
+/// How many places Ctrl+Z can go back through.
+const HISTORY_LENGTH: usize = 100;
+/// How long a frame step button has to be held before it starts repeating, in seconds.
+const STEP_REPEAT_DELAY: f32 = 0.4;
+/// Time between repeated steps while a frame step button is held, in seconds.
+const STEP_REPEAT_INTERVAL: f32 = 1.0 / 20.0;
+/// How close, in pixels, a measurement endpoint has to be to a box edge to snap onto it.
+const MEASURE_SNAP_DISTANCE: f32 = 8.0;
+/// Outline of boxes that are only in the loaded file when comparing.
+const DIFF_ADDED_COLOR: Color32 = Color32::from_rgb(0, 220, 255);
+/// Outline of boxes that are only in the comparison file.
+const DIFF_REMOVED_COLOR: Color32 = Color32::from_rgb(255, 0, 255);
+/// Hitbox outlines on actions with more than one hit ID, by the order the hit IDs come out in.
+const HIT_ID_COLORS: [Color32; 5] = [
+    Color32::from_rgb(255, 215, 0),
+    Color32::from_rgb(0, 230, 140),
+    Color32::from_rgb(255, 110, 180),
+    Color32::from_rgb(130, 160, 255),
+    Color32::from_rgb(190, 255, 80),
+];
+
+#[derive(Default, Clone)]
+struct Vector3f {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+/// One captured frame of a GIF export, handed to the app to take the screenshot.
+pub struct GifFrame {
+    /// Canvas rect to crop the screenshot to.
+    pub rect: Rect,
+    /// Set on the action's last frame, when the GIF should be written out.
+    pub last: bool,
+    pub file_name: String,
+    pub fps: u32,
 }
 
-#[derive(Default, FromPrimitive, PartialEq, Eq, Clone)]
-enum SteerOperationType {
+/// Which view fills the main panel.
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+enum ViewerTab {
     #[default]
-    Nop = 0,
-    Set = 1,
-    Add = 2,
-    Multiply = 3,
-    SetSign = 4,
-    AddSign = 5,
-    SetNegativeX = 6,
-    SetNegativeY = 7,
-    SetNegativeZ = 8,
-    SetMinimum = 9,
-    SetMaximum = 10,
-    SetIgnore = 11,
-    SetInherit = 12,
-    SetTarget = 13,
-    SetHomingValue = 14,
-    SetHomingTime = 15,
-    SetInheritXYZ = 16,
+    Boxes,
+    FrameData,
+    Cancels,
+    RawKeys,
 }
 
-#[derive(Default, FromPrimitive, PartialEq, Eq, Clone)]
-enum SteerValueType {
+/// What playback does when it reaches the end of the action.
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+enum PlaybackMode {
+    /// Plays through to the end and stops, looping the action's own loop region as many times
+    /// as its loop count says, like the game.
     #[default]
-    VelocityX = 0,
-    VelocityY = 1,
-    VelocityZ = 2,
-    AccelerationX = 3,
-    AccelerationY = 4,
-    AccelerationZ = 5,
+    Once,
+    /// Starts again from frame 1.
+    Loop,
+    /// Plays backwards to frame 1, then forwards again.
+    PingPong,
 }
 
-#[derive(Default, Clone)]
-struct CollisionBox {
-    x: f32,
-    y: f32,
-    width: f32,
-    height: f32,
+/// Where the canvas was looking, remembered per character.
+#[derive(Clone, Copy, Deserialize, Serialize)]
+pub struct SavedView {
+    pub offset_x: f32,
+    pub offset_y: f32,
+    pub zoom: f32,
 }
 
-#[derive(Default)]
-struct PushCollisionKey {
-    condition: u8,
-    attribute: u16,
-    pushbox: CollisionBox,
+/// A place the user has been, for undo and redo.
+#[derive(Clone)]
+struct HistoryEntry {
+    character_name: String,
+    selected_index: i32,
+    frame: usize,
+    offset_x: f32,
+    offset_y: f32,
+    zoom: f32,
 }
 
-#[derive(Default)]
-struct DamageCollisionKey {
-    condition: u8,
-    collision_type: u8,
-    immune: u8,
-    extend: u8,
-    level: u8,
-    type_flag: u32,
-    boxes: Vec<CollisionBox>,
+/// A frame flagged to come back to, remembered per character.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct Bookmark {
+    pub character_name: String,
+    /// Entry of the action list.
+    pub selected_index: i32,
+    pub frame: usize,
+    pub label: String,
 }
 
-#[derive(Default)]
-struct AttackCollisionKey {
-    condition: u8,
-    collision_type: u8,
-    hit_id: i8,
-    guard_bit: u8,
-    kind_flag: u32,
-    hit_offset: [i32; 2],
-    boxes: Vec<CollisionBox>,
+/// How far one movement action carries the character, found by running it through the sim.
+#[derive(Clone, Copy)]
+struct Movement {
+    distance: f32,
+    height: f32,
+    frames: usize,
+    landing_frame: Option<usize>,
 }
 
-#[derive(Default, Ord, PartialOrd, Eq, PartialEq)]
-struct Trigger {
-    action: i32,
-    condition_flag: u32,
+/// How far the character's attack boxes reach over the whole action, in game units from where
+/// the action starts, measured along the ground.
+#[derive(Clone, Copy)]
+struct Reach {
+    forward: f32,
+    /// Displayed frame `forward` is reached on.
+    frame: usize,
+    low: f32,
+    high: f32,
 }
 
+/// Character-wide numbers for the stats panel, worked out once when the file is opened.
 #[derive(Default)]
-struct Vector3f {
-    x: f32,
-    y: f32,
-    z: f32,
+struct CharacterStats {
+    personal_data: Option<PersonalData>,
+    walk_forward: Option<Movement>,
+    walk_back: Option<Movement>,
+    dash_forward: Option<Movement>,
+    dash_back: Option<Movement>,
+    prejump: Option<Movement>,
+    neutral_jump: Option<Movement>,
+    forward_jump: Option<Movement>,
+    back_jump: Option<Movement>,
 }
 
-#[derive(Default)]
-struct ActionInfo {
-    first_active_frame: i32,
-    recovery_frame: i32,
-    end_frame: i32,
-    loop_count: i32,
+/// Everything `update_position` reads and writes, so a frame can be restored without replaying
+/// the action from the start.
+#[derive(Default, Clone)]
+struct MotionState {
+    position: Vector3f,
+    velocity: Vector3f,
+    acceleration: Vector3f,
+    prev_position: Vector3f,
+    prev_velocity: Vector3f,
+    prev_acceleration: Vector3f,
+    root_motion: Vector3f,
+    object_root_motion: Vec<Option<Vector3f>>,
+    homing_time: f32,
+    homing_value: f32,
+    landing_frame: Option<usize>,
+}
+
+impl MotionState {
+    /// Where an object's boxes are moved to, the same way `Viewer::world_box` moves them.
+    fn object_position(&self, object_id: usize) -> (f32, f32) {
+        let root_motion = match self.object_root_motion.get(object_id) {
+            Some(Some(root_motion)) => root_motion,
+            _ => &self.root_motion,
+        };
+        (
+            self.position.x + root_motion.x,
+            self.position.y + root_motion.y,
+        )
+    }
 }
 
 struct ProjectileKey {
@@ -153,20 +205,130 @@ impl Default for ProjectileKey {
     }
 }
 
+/// Frame data of one action, for lining it up against other characters' version of it.
+#[derive(Clone)]
+pub struct ActionSummary {
+    pub name: String,
+    pub frames: usize,
+    pub startup: Option<i32>,
+    pub active: Vec<(usize, usize)>,
+    pub recovery: Option<i32>,
+}
+
+impl ActionSummary {
+    /// Frame data of the first action with ID `action_id`, if the file has one.
+    pub fn from_action(
+        fchar: &CharacterAsset,
+        character: Character,
+        action_id: i32,
+    ) -> Option<ActionSummary> {
+        let index = fchar
+            .action_list
+            .iter()
+            .position(|action| action.info.action_data.action_id == action_id)?;
+        let dump = extract::dump_action(fchar, character, index);
+        Some(ActionSummary {
+            name: dump.name,
+            frames: dump.frames.len(),
+            startup: dump.startup,
+            active: dump.active,
+            recovery: dump.recovery,
+        })
+    }
+
+    /// Active frames over every active window.
+    pub fn active_frames(&self) -> Option<usize> {
+        if self.active.is_empty() {
+            return None;
+        }
+        Some(self.active.iter().map(|(start, end)| end - start + 1).sum())
+    }
+}
+
+#[derive(Serialize)]
+struct FrameExport<'a> {
+    frame: usize,
+    push_collision_keys: &'a [PushCollisionKey],
+    damage_collision_keys: &'a [DamageCollisionKey],
+    attack_collision_keys: &'a [AttackCollisionKey],
+}
+
 pub struct Viewer {
     pub asset: Option<CharacterAsset>,
     pub character: Character,
+    /// Display name of the open character or file, used when copying frame data.
+    pub character_name: String,
+    pub bookmarks: Vec<Bookmark>,
+    /// Where the user has been, oldest first, for Ctrl+Z and Ctrl+Y. Only jumps get their own
+    /// entry; stepping a frame, playback, panning and zooming update the current one.
+    history: Vec<HistoryEntry>,
+    history_cursor: usize,
+    /// The current history entry is on another character, which the app has to open first.
+    history_pending: bool,
+    /// Label for the next bookmark added.
+    bookmark_label: String,
+    /// Why the last file failed to load, shown until the next file opens.
+    pub load_error: Option<String>,
+    /// Fields of the open file that aren't where the box extraction expects them, which means
+    /// it's probably from a different game version.
+    pub schema_warnings: Vec<String>,
+    pub box_colors: BoxColors,
+    /// `box_colors` adjusted for the current theme, refreshed every time the canvas is drawn.
+    draw_colors: BoxColors,
     selected_index: i32,
     action_index: i32,
-    action_index_string: String,
-    action_name_filter_string: String,
+    action_search_string: String,
+    /// Entries of the action list sharing the index last searched for, when there's more than
+    /// one to pick from.
+    search_matches: Vec<usize>,
+    only_attacks: bool,
+    /// Per entry of the action list, whether the action has any hitboxes. Worked out once when
+    /// a file is opened.
+    action_has_attacks: Vec<bool>,
+    character_stats: CharacterStats,
+    /// Another version of the same character's file to compare the loaded one against.
+    compare_asset: Option<CharacterAsset>,
+    compare_name: String,
+    show_diff: bool,
+    action_diff: Option<ActionDiff>,
+    /// The action `action_diff` was worked out for.
+    diff_index: i32,
     current_frame: usize,
     action_info: ActionInfo,
+    active_windows: Vec<(usize, usize)>,
+    hit_windows: Vec<HitWindow>,
+    cancel_windows: Vec<CancelWindow>,
+    /// What can cancel into each action ID, built when the file is opened.
+    cancel_sources: Option<HashMap<i32, Vec<CancelWindow>>>,
+    timeline: Vec<TimelineCell>,
+    branches: Vec<Branch>,
+    motion_clips: Vec<MotionClip>,
+    frame_cache: Vec<FrameBoxes>,
+    frame_velocities: Vec<(f32, f32)>,
+    /// Where the character is on each frame, including root motion.
+    frame_positions: Vec<(f32, f32)>,
+    /// Values that replace what the steer keys of each `SteerValueType` set or add, for trying
+    /// out changes to a move.
+    steer_overrides: [Option<f32>; 6],
+    /// `frame_positions` without the overrides, while any are set.
+    original_positions: Vec<(f32, f32)>,
+    /// The sim state entering each frame. Empty until the sim has run over the action with its
+    /// current inputs.
+    frame_motion: Vec<MotionState>,
+    /// Game-space bounds of every box over the whole action, with Y pointing up.
+    action_bounds: Option<Rect>,
+    action_reach: Option<Reach>,
+    cached_index: i32,
+    /// Every key on one frame for the raw keys tab, with the selected index and displayed frame
+    /// they were read for.
+    raw_keys: Vec<RawKey>,
+    raw_keys_frame: Option<(i32, usize)>,
     push_collision_keys: Vec<PushCollisionKey>,
     damage_collision_keys: Vec<DamageCollisionKey>,
     attack_collision_keys: Vec<AttackCollisionKey>,
     projectile_keys: Vec<ProjectileKey>,
     triggers: Vec<Trigger>,
+    missing_boxes: Vec<MissingBox>,
     position: Vector3f,
     velocity: Vector3f,
     acceleration: Vector3f,
@@ -174,10 +336,111 @@ pub struct Viewer {
     prev_velocity: Vector3f,
     prev_acceleration: Vector3f,
     root_motion: Vector3f,
+    object_root_motion: Vec<Option<Vector3f>>,
+    target_distance: f32,
+    show_dummy: bool,
+    /// The dummy opponent's pushbox, standing at `target_distance`.
+    dummy_pushbox: CollisionBox,
+    dragging_dummy: bool,
+    /// Where the canvas was last right-clicked, and what boxes were under the pointer then.
+    context_menu_pos: Option<Pos2>,
+    context_menu_boxes: Vec<String>,
+    homing_time: f32,
+    homing_value: f32,
+    /// The displayed frame the character touched down on, once the sim has landed an airborne
+    /// character this action.
+    landing_frame: Option<usize>,
+    /// Where the whole action lands, from the last run of `get_frame_velocities`.
+    action_landing_frame: Option<usize>,
+    /// A SetInherit or SetInheritXYZ key has carried over velocity or acceleration since the
+    /// sim was last reset.
+    inherits_motion: bool,
+    /// Whether the whole action inherits motion, from the last run of `get_frame_velocities`.
+    action_inherits_motion: bool,
+    /// The sim state after the last frame, from the last run of `get_frame_velocities`.
+    action_end_motion: MotionState,
+    /// The motion the previous action was left with when this one was reached through a branch
+    /// or cancel, with the index of the action it was carried into. Only velocity and
+    /// acceleration are kept, for SetInherit keys to pick up.
+    carried_motion: Option<(i32, MotionState)>,
     offset_x: f32,
     offset_y: f32,
+    /// Screen pixels per game unit.
+    zoom: f32,
+    fit_pending: bool,
+    /// Keep panning so the character stays in the middle of the canvas as it moves.
+    follow_character: bool,
+    default_offset_x: f32,
+    default_offset_y: f32,
+    /// Screen position of the origin when a file opens with no remembered view. Tall characters
+    /// get their origin moved down further so the standing pushbox fits.
+    pub default_origin: [f32; 2],
+    /// Pan and zoom of each character that has been opened, by display name.
+    pub views: HashMap<String, SavedView>,
+    /// Starred entries of the action list for each character, by display name.
+    pub favorites: HashMap<String, Vec<i32>>,
+    /// Corner radius and outline width of the boxes, in points.
+    pub box_rounding: f32,
+    pub box_stroke_width: f32,
+    /// Skip fitting the next selected action to the view, because a remembered view was just
+    /// restored.
+    keep_view: bool,
+    ground_y: f32,
+    /// Extra downward acceleration while airborne, in units/frame². The game's jump arcs come
+    /// entirely from the actions' SteerKeys, so this is 0 unless a user wants to experiment.
+    gravity: f32,
+    /// Stop the character at a wall `wall_x` units from the origin. A negative distance puts
+    /// the wall behind the character.
+    use_wall: bool,
+    wall_x: f32,
+    /// The sim stopped the character at the wall on the last update.
+    wall_pinned: bool,
+    /// The whole action ends with the character against the wall.
+    action_wall_pinned: bool,
     last_cursor_pos: Pos2,
+    show_ghost_frame: bool,
+    show_box_values: bool,
+    /// `kind_flag` bit an attack needs for its frames to be red on the timeline, or 0 for any
+    /// attack.
+    timeline_kind_filter: u32,
+    show_trajectory: bool,
+    show_trail: bool,
+    show_disjoint: bool,
+    show_all_hitboxes: bool,
+    tab: ViewerTab,
+    /// Stepping jumps over frames with the same boxes and cancels as the one before.
+    skip_unchanged: bool,
+    trail_length: usize,
+    /// Where the character was on the frames played through in order, oldest first.
+    trail: Vec<(f32, f32)>,
+    /// The frame and action the trail last had a position added for.
+    trail_frame: (i32, usize),
+    facing_left: bool,
+    measuring: bool,
+    override_stun: bool,
+    custom_stun: i32,
+    measure_start: Option<Pos2>,
+    measure_end: Option<Pos2>,
+    /// Where the box canvas was last drawn, in points.
+    canvas_rect: Rect,
+    png_export_requested: bool,
+    /// Next frame to capture while a GIF export is running.
+    gif_capture_frame: Option<usize>,
+    /// Set when the canvas was drawn at `gif_capture_frame` this update. The export button is
+    /// below the canvas, so the update it's clicked on doesn't show frame 1 yet.
+    gif_frame_drawn: bool,
+    /// Frame to go back to once the GIF export is done.
+    gif_restore_frame: usize,
+    pub gif_fps: u32,
     should_update: bool,
+    playing: bool,
+    playback_speed: f32,
+    playback_mode: PlaybackMode,
+    /// Ping-pong playback is on its way back to frame 1.
+    playing_backwards: bool,
+    playback_timer: f32,
+    /// How long a frame step button has been held, and how many times it has repeated since.
+    step_held: Option<(f32, usize)>,
 }
 
 impl Default for Viewer {
@@ -185,17 +448,55 @@ impl Default for Viewer {
         Self {
             asset: None,
             character: Character::Common,
+            character_name: "".to_string(),
+            bookmarks: vec![],
+            history: vec![],
+            history_cursor: 0,
+            history_pending: false,
+            favorites: HashMap::new(),
+            bookmark_label: "".to_string(),
+            load_error: None,
+            schema_warnings: vec![],
+            box_colors: Default::default(),
+            draw_colors: Default::default(),
             selected_index: -1,
             action_index: 0,
-            action_index_string: "".to_string(),
-            action_name_filter_string: "".to_string(),
+            action_search_string: "".to_string(),
+            search_matches: vec![],
+            only_attacks: false,
+            action_has_attacks: vec![],
+            character_stats: Default::default(),
+            compare_asset: None,
+            compare_name: "".to_string(),
+            show_diff: true,
+            action_diff: None,
+            diff_index: -1,
             current_frame: 0,
             action_info: Default::default(),
+            active_windows: vec![],
+            hit_windows: vec![],
+            cancel_windows: vec![],
+            cancel_sources: None,
+            timeline: vec![],
+            branches: vec![],
+            motion_clips: vec![],
+            frame_cache: vec![],
+            frame_velocities: vec![],
+            frame_positions: vec![],
+            steer_overrides: Default::default(),
+            original_positions: vec![],
+            frame_motion: vec![],
+            action_bounds: None,
+            action_reach: None,
+            cached_index: -1,
+            raw_keys: vec![],
+            raw_keys_frame: None,
             push_collision_keys: vec![],
             damage_collision_keys: vec![],
             attack_collision_keys: vec![],
             projectile_keys: vec![],
             triggers: vec![],
+            missing_boxes: vec![],
             position: Default::default(),
             velocity: Default::default(),
             acceleration: Default::default(),
@@ -203,241 +504,766 @@ impl Default for Viewer {
             prev_velocity: Default::default(),
             prev_acceleration: Default::default(),
             root_motion: Default::default(),
+            object_root_motion: vec![],
+            target_distance: 150.0,
+            show_dummy: false,
+            dummy_pushbox: CollisionBox {
+                x: 0.0,
+                y: 70.0,
+                width: 25.0,
+                height: 70.0,
+            },
+            dragging_dummy: false,
+            context_menu_pos: None,
+            context_menu_boxes: vec![],
+            homing_time: 0.0,
+            homing_value: 0.0,
+            landing_frame: None,
+            action_landing_frame: None,
+            inherits_motion: false,
+            action_inherits_motion: false,
+            action_end_motion: Default::default(),
+            carried_motion: None,
             offset_x: 90.0,
             offset_y: 300.0,
+            zoom: 1.0,
+            fit_pending: false,
+            follow_character: false,
+            default_offset_x: 90.0,
+            default_offset_y: 300.0,
+            default_origin: [90.0, 300.0],
+            views: HashMap::new(),
+            box_rounding: 0.0,
+            box_stroke_width: 1.0,
+            keep_view: false,
+            ground_y: 0.0,
+            gravity: 0.0,
+            use_wall: false,
+            wall_x: -300.0,
+            wall_pinned: false,
+            action_wall_pinned: false,
             last_cursor_pos: Default::default(),
+            show_ghost_frame: false,
+            show_box_values: false,
+            timeline_kind_filter: 0,
+            show_trajectory: false,
+            show_trail: false,
+            show_disjoint: false,
+            show_all_hitboxes: false,
+            tab: Default::default(),
+            skip_unchanged: false,
+            trail_length: 10,
+            trail: vec![],
+            trail_frame: (-1, 0),
+            facing_left: false,
+            measuring: false,
+            override_stun: false,
+            custom_stun: 15,
+            measure_start: None,
+            measure_end: None,
+            canvas_rect: Rect::NOTHING,
+            png_export_requested: false,
+            gif_capture_frame: None,
+            gif_frame_drawn: false,
+            gif_restore_frame: 1,
+            gif_fps: 20,
             should_update: false,
+            playing: false,
+            playback_speed: 1.0,
+            playback_mode: PlaybackMode::Once,
+            playing_backwards: false,
+            playback_timer: 0.0,
+            step_held: None,
         }
     }
 }
-fn steer_key_to_value(
-    op_type: SteerOperationType,
-    in_value: f32,
-    prev_value: f32,
-    modify_value: f32,
-) -> f32 {
-    let mut value = in_value;
-    match op_type {
-        SteerOperationType::Nop => {}
-        SteerOperationType::Set => value = modify_value,
-        SteerOperationType::Add => value += modify_value,
-        SteerOperationType::Multiply => value *= modify_value,
-        SteerOperationType::SetSign => {}
-        SteerOperationType::AddSign => {}
-        SteerOperationType::SetNegativeX => {
-            if value < 0f32 && prev_value > 0f32 {
-                value = modify_value;
-            } else if value > 0f32 && prev_value < 0f32 {
-                value = modify_value;
-            }
-        }
-        SteerOperationType::SetNegativeY => {
-            if value < 0f32 && prev_value > 0f32 {
-                value = modify_value;
-            } else if value > 0f32 && prev_value < 0f32 {
-                value = modify_value;
-            }
-        }
-        SteerOperationType::SetNegativeZ => {
-            if value < 0f32 && prev_value > 0f32 {
-                value = modify_value;
-            } else if value > 0f32 && prev_value < 0f32 {
-                value = modify_value;
-            }
-        }
-        SteerOperationType::SetMinimum => {
-            if value < modify_value {
-                value = modify_value;
-            }
-        }
-        SteerOperationType::SetMaximum => {
-            if value > modify_value {
-                value = modify_value;
-            }
-        }
-        SteerOperationType::SetIgnore => {}
-        SteerOperationType::SetInherit => {}
-        SteerOperationType::SetTarget => {}
-        SteerOperationType::SetHomingValue => {}
-        SteerOperationType::SetHomingTime => {}
-        SteerOperationType::SetInheritXYZ => {}
-    }
-    value
-}
+/// Names for each bit of a TriggerKey's condition flag, in bit order.
+const CANCEL_FLAGS: [(u32, &str); 22] = [
+    (1 << 0, "Hit"),
+    (1 << 1, "Guard"),
+    (1 << 2, "Whiff"),
+    (1 << 3, "Armor"),
+    (1 << 4, "Jump"),
+    (1 << 5, "SuperJump"),
+    (1 << 6, "Defer"),
+    (1 << 7, "Fly"),
+    (1 << 8, "WallBk"),
+    (1 << 10, "Counter"),
+    (1 << 11, "Strike"),
+    (1 << 12, "Parry"),
+    (1 << 13, "Just"),
+    (1 << 14, "Normal"),
+    (1 << 15, "Easy"),
+    (1 << 16, "Extra"),
+    (1 << 17, "Inhibit"),
+    (1 << 18, "VJump"),
+    (1 << 19, "FJump"),
+    (1 << 20, "BJump"),
+    (1 << 21, "Throw"),
+    (1 << 22, "Terminator"),
+];
 
-impl Viewer {
-    pub fn open_fchar(&mut self, buffer: Vec<u8>) -> bool {
-        parse_json(include_bytes_zstd!("assets/rszsf6.json", 9)).unwrap();
-        let fchar = parse_fchar(&buffer);
-        match fchar {
-            Ok(fchar) => {
-                self.asset = Some(fchar.1);
-                self.selected_index = -1;
-                self.action_index = 0;
-                self.current_frame = 1;
-                true
+/// Condition bits that decide when a cancel is available, and the cancel list heading for each.
+const CANCEL_CONDITIONS: [(u32, &str); 3] = [
+    (1 << 0, "On Hit"),
+    (1 << 1, "On Block"),
+    (1 << 2, "On Whiff"),
+];
+
+/// Sorts triggers under "Always" when they don't depend on the outcome (all or none of the
+/// hit/guard/whiff bits set), or under every outcome they do allow otherwise. Within a group an
+/// action is listed once, with the flags of all its triggers combined.
+fn group_triggers(triggers: &[Trigger]) -> Vec<(&'static str, Vec<Trigger>)> {
+    let all_conditions = CANCEL_CONDITIONS.iter().fold(0, |flags, (bit, _)| flags | bit);
+    let mut groups: Vec<(&'static str, Vec<Trigger>)> = vec![("Always", vec![])];
+    for (_, name) in CANCEL_CONDITIONS {
+        groups.push((name, vec![]));
+    }
+    for trigger in triggers {
+        let conditions = trigger.condition_flag & all_conditions;
+        for (group_name, group) in groups.iter_mut() {
+            let in_group = match CANCEL_CONDITIONS.iter().find(|(_, name)| name == group_name) {
+                Some((bit, _)) => conditions != all_conditions && conditions & bit > 0,
+                None => conditions == all_conditions || conditions == 0,
+            };
+            if !in_group {
+                continue;
+            }
+            match group.iter_mut().find(|grouped| grouped.action == trigger.action) {
+                Some(grouped) => grouped.condition_flag |= trigger.condition_flag,
+                None => group.push(trigger.clone()),
             }
-            Err(_) => false,
         }
     }
+    groups
+}
 
-    fn get_action_name(&self, action_index: i32) -> String {
-        match self.character {
-            Character::Common => {
-                let action_name: action_names::CommonActions =
-                    num::FromPrimitive::from_i32(action_index).unwrap_or_default();
-                match action_name {
-                    action_names::CommonActions::Common_INVALID_ID => format!("{}", action_index),
-                    _ => action_name.to_string(),
-                }
-            }
-            Character::Ryu => {
-                let action_name: action_names::RyuActions =
-                    num::FromPrimitive::from_i32(action_index).unwrap_or_default();
-                match action_name {
-                    action_names::RyuActions::Ryu_INVALID_ID => format!("{}", action_index),
-                    _ => action_name.to_string(),
-                }
-            }
-            Character::Luke => {
-                let action_name: action_names::LukeActions =
-                    num::FromPrimitive::from_i32(action_index).unwrap_or_default();
-                match action_name {
-                    action_names::LukeActions::Luke_INVALID_ID => format!("{}", action_index),
-                    _ => action_name.to_string(),
-                }
-            }
-            Character::Kimberly => {
-                let action_name: action_names::KimberlyActions =
-                    num::FromPrimitive::from_i32(action_index).unwrap_or_default();
-                match action_name {
-                    action_names::KimberlyActions::Kimberly_INVALID_ID => {
-                        format!("{}", action_index)
+/// The frames of a set of cancel windows, such as "frames 10-14, 20-25".
+fn cancel_windows_text(windows: &[&CancelWindow]) -> String {
+    match windows[..] {
+        [] => "".to_string(),
+        [cancel_window] if cancel_window.first_frame == cancel_window.last_frame => {
+            format!("frame {}", cancel_window.first_frame)
+        }
+        _ => {
+            let ranges: Vec<String> = windows
+                .iter()
+                .map(|cancel_window| {
+                    if cancel_window.first_frame == cancel_window.last_frame {
+                        cancel_window.first_frame.to_string()
+                    } else {
+                        format!("{}-{}", cancel_window.first_frame, cancel_window.last_frame)
                     }
-                    _ => action_name.to_string(),
-                }
-            }
-            Character::ChunLi => {
-                let action_name: action_names::ChunLiActions =
-                    num::FromPrimitive::from_i32(action_index).unwrap_or_default();
-                match action_name {
-                    action_names::ChunLiActions::ChunLi_INVALID_ID => format!("{}", action_index),
-                    _ => action_name.to_string(),
-                }
+                })
+                .collect();
+            format!("frames {}", ranges.join(", "))
+        }
+    }
+}
+
+/// Names the set bits of a TriggerKey's condition flag. Bits without a name are shown in hex
+/// rather than dropped.
+fn cancel_flags_to_string(flags: u32) -> String {
+    flags_to_string(flags, &CANCEL_FLAGS, " | ").unwrap_or_default()
+}
+
+/// The names a table of guessed bit names gives `flag`, as " (unverified: crouching)" to
+/// follow the raw value. Empty when no bit is set. The guessed tables haven't been checked
+/// against the game, so their names are only shown as a hint next to the raw value.
+fn unverified_names(flag: u32, names: &[(u32, &str)]) -> String {
+    match flags_to_string(flag, names, "+") {
+        Some(names) => format!(" (unverified: {})", names),
+        None => "".to_string(),
+    }
+}
+
+fn hit_data_to_string(hit_data: &HitData) -> String {
+    let mut lines: Vec<String> = vec![format!(
+        "  Hitstop: {} (self), {} (opponent)",
+        hit_data.hit_stop_owner, hit_data.hit_stop_target
+    )];
+    for (situation, damage) in &hit_data.damage {
+        lines.push(format!("  Damage #{}: {}", situation, damage));
+    }
+    for reaction in &hit_data.reactions {
+        lines.push(format!(
+            "  Reaction #{}: stun {}, pushback {}, {}",
+            reaction.situation, reaction.time, reaction.dest_x, reaction.dest_y
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Centre and full size of a box, relative to the character, in game units.
+fn box_values(collision_box: &CollisionBox) -> String {
+    format!(
+        "({}, {}) {}x{}",
+        collision_box.x,
+        collision_box.y,
+        collision_box.width * 2f32,
+        collision_box.height * 2f32
+    )
+}
+
+/// Which of the action's objects a box came from, as " (object n)", for tooltips. The
+/// character's own boxes on object 0 get nothing.
+fn object_label(object_id: usize) -> String {
+    match object_id {
+        0 => "".to_string(),
+        object_id => format!(" (object {})", object_id),
+    }
+}
+
+/// Outlines `rect` with dashes, which marks boxes that don't deal damage.
+fn dashed_rect(painter: &egui::Painter, rect: Rect, stroke: Stroke) {
+    painter.extend(egui::Shape::dashed_line(
+        &[
+            rect.left_top(),
+            rect.right_top(),
+            rect.right_bottom(),
+            rect.left_bottom(),
+            rect.left_top(),
+        ],
+        stroke,
+        4.0,
+        3.0,
+    ));
+}
+
+/// Strike boxes are placed relative to their attack key's hit offset.
+fn offset_box(collision_box: &CollisionBox, offset: [i32; 2]) -> CollisionBox {
+    CollisionBox {
+        x: collision_box.x + offset[0] as f32,
+        y: collision_box.y + offset[1] as f32,
+        width: collision_box.width,
+        height: collision_box.height,
+    }
+}
+
+/// The parts of `rect` that aren't covered by any of `cuts`, as non-overlapping rects.
+fn subtract_rects(rect: Rect, cuts: &[Rect]) -> Vec<Rect> {
+    let mut pieces = vec![rect];
+    for cut in cuts {
+        let mut remaining: Vec<Rect> = vec![];
+        for piece in pieces {
+            let overlap = piece.intersect(*cut);
+            if overlap.width() <= 0.0 || overlap.height() <= 0.0 {
+                remaining.push(piece);
+                continue;
             }
-            Character::Manon => {
-                let action_name: action_names::ManonActions =
-                    num::FromPrimitive::from_i32(action_index).unwrap_or_default();
-                match action_name {
-                    action_names::ManonActions::Manon_INVALID_ID => format!("{}", action_index),
-                    _ => action_name.to_string(),
+            // Above and below the cut, then either side of it within its rows.
+            let candidates = [
+                Rect::from_min_max(piece.min, Pos2::new(piece.max.x, overlap.min.y)),
+                Rect::from_min_max(Pos2::new(piece.min.x, overlap.max.y), piece.max),
+                Rect::from_min_max(
+                    Pos2::new(piece.min.x, overlap.min.y),
+                    Pos2::new(overlap.min.x, overlap.max.y),
+                ),
+                Rect::from_min_max(
+                    Pos2::new(overlap.max.x, overlap.min.y),
+                    Pos2::new(piece.max.x, overlap.max.y),
+                ),
+            ];
+            for candidate in candidates {
+                if candidate.width() > 0.0 && candidate.height() > 0.0 {
+                    remaining.push(candidate);
                 }
             }
-            Character::Zangief => {
-                let action_name: action_names::ZangiefActions =
-                    num::FromPrimitive::from_i32(action_index).unwrap_or_default();
-                match action_name {
-                    action_names::ZangiefActions::Zangief_INVALID_ID => format!("{}", action_index),
-                    _ => action_name.to_string(),
-                }
+        }
+        pieces = remaining;
+    }
+    pieces
+}
+
+/// Moves `pos` onto the nearest edge of any of `box_rects` if one is within snapping distance.
+fn snap_to_box_edge(pos: Pos2, box_rects: &[Rect]) -> Pos2 {
+    let mut snapped = pos;
+    let mut nearest = MEASURE_SNAP_DISTANCE;
+    for rect in box_rects {
+        let mut edge = rect.clamp(pos);
+        if edge == pos {
+            // Inside the box, so push out to whichever edge is closest.
+            let left = pos.x - rect.min.x;
+            let right = rect.max.x - pos.x;
+            let top = pos.y - rect.min.y;
+            let bottom = rect.max.y - pos.y;
+            let closest = left.min(right).min(top).min(bottom);
+            if closest == left {
+                edge.x = rect.min.x;
+            } else if closest == right {
+                edge.x = rect.max.x;
+            } else if closest == top {
+                edge.y = rect.min.y;
+            } else {
+                edge.y = rect.max.y;
             }
-            Character::JP => {
-                let action_name: action_names::JPActions =
-                    num::FromPrimitive::from_i32(action_index).unwrap_or_default();
-                match action_name {
-                    action_names::JPActions::JP_INVALID_ID => format!("{}", action_index),
-                    _ => action_name.to_string(),
-                }
+        }
+        let distance = edge.distance(pos);
+        if distance < nearest {
+            nearest = distance;
+            snapped = edge;
+        }
+    }
+    snapped
+}
+
+/// Velocity that covers `distance` over the remaining homing frames, capped at `max_speed` when
+/// a homing value has been set. This reading of the homing keys is a guess that hasn't been
+/// checked against the game.
+fn homing_velocity(distance: f32, frames: f32, max_speed: f32) -> f32 {
+    let velocity = distance / frames.max(1f32);
+    if max_speed > 0f32 {
+        return velocity.clamp(-max_speed, max_speed);
+    }
+    velocity
+}
+
+/// Whether a SteerKey on `value_type` changes anything this frame. `ignored` holds the value
+/// types a SetIgnore key has locked for the rest of the frame, and a SetIgnore key itself only
+/// adds to it.
+fn steer_key_applies(
+    ignored: &mut [bool; 6],
+    op_type: &SteerOperationType,
+    value_type: &SteerValueType,
+) -> bool {
+    let index = value_type.clone() as usize;
+    if ignored[index] {
+        return false;
+    }
+    if *op_type == SteerOperationType::SetIgnore {
+        ignored[index] = true;
+        return false;
+    }
+    true
+}
+
+/// The displayed frame a character lands on, if the update of key frame `frame` took it from
+/// the air to height `y`. Only a character coming down from the air lands, not one that was
+/// grounded and got pushed into the floor. The `+ 2` is one because displayed frames count
+/// from 1 where key frames count from 0, and one because the state after the update is the one
+/// entering the next frame, which is the first frame on the ground.
+fn landing_frame(frame: i32, airborne: bool, y: f32, ground_y: f32) -> Option<usize> {
+    if airborne && y <= ground_y {
+        return Some(frame.max(0) as usize + 2);
+    }
+    None
+}
+
+impl Viewer {
+    /// Loads a new file, keeping the current one if it can't be parsed.
+    pub fn open_fchar(&mut self, buffer: Vec<u8>) -> Result<(), String> {
+        let fchar = extract::open(&buffer)?;
+        // Leave room above the origin for the standing pushbox, so tall characters
+        // start fully in view. The dummy opponent borrows the same pushbox.
+        let mut standing_height = 0f32;
+        if !fchar.action_list.is_empty() {
+            let standing_boxes = extract::get_boxes(&fchar, 0, 0);
+            standing_height = standing_boxes
+                .push_collision_keys
+                .iter()
+                .map(|key| key.pushbox.y + key.pushbox.height)
+                .fold(0f32, f32::max);
+            match standing_boxes.push_collision_keys.first() {
+                Some(key) => self.dummy_pushbox = key.pushbox.clone(),
+                None => (),
             }
-            Character::Dhalsim => {
-                let action_name: action_names::DhalsimActions =
-                    num::FromPrimitive::from_i32(action_index).unwrap_or_default();
-                match action_name {
-                    action_names::DhalsimActions::Dhalsim_INVALID_ID => format!("{}", action_index),
-                    _ => action_name.to_string(),
-                }
+        }
+        self.default_offset_x = self.default_origin[0];
+        self.default_offset_y = (standing_height + 150.0).max(self.default_origin[1]);
+        self.offset_x = self.default_offset_x;
+        self.offset_y = self.default_offset_y;
+        self.zoom = 1.0;
+        self.action_has_attacks = (0..fchar.action_list.len())
+            .map(|index| extract::has_attack_boxes(&fchar, index))
+            .collect();
+        self.schema_warnings = extract::check_box_key_fields(&fchar);
+        self.asset = Some(fchar);
+        self.cancel_sources = self.asset.as_ref().map(extract::get_cancel_sources);
+        self.character_stats = self.get_character_stats();
+        self.close_comparison();
+        self.selected_index = -1;
+        self.cached_index = -1;
+        self.raw_keys_frame = None;
+        self.action_index = 0;
+        self.current_frame = 1;
+        self.search_matches.clear();
+        Ok(())
+    }
+
+    /// Loads a second file to compare the current one against. It should be another version of
+    /// the same character, since actions are matched up by ID.
+    pub fn open_comparison(&mut self, buffer: Vec<u8>, name: String) -> Result<(), String> {
+        self.compare_asset = Some(extract::open(&buffer)?);
+        self.compare_name = name;
+        self.action_diff = None;
+        self.diff_index = -1;
+        Ok(())
+    }
+
+    pub fn close_comparison(&mut self) {
+        self.compare_asset = None;
+        self.compare_name = "".to_string();
+        self.action_diff = None;
+        self.diff_index = -1;
+    }
+
+    fn get_action_diff(&mut self) {
+        self.action_diff = match (&self.asset, &self.compare_asset) {
+            (Some(fchar), Some(other)) => Some(diff::diff_action(
+                fchar,
+                self.selected_index as usize,
+                other,
+            )),
+            _ => None,
+        };
+        self.diff_index = self.selected_index;
+    }
+
+    fn render_comparison(&mut self, ui: &mut egui::Ui) {
+        ui.label(format!(
+            "Changes from {} to {}",
+            self.compare_name, self.character_name
+        ));
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.show_diff, "Show on canvas").on_hover_text(
+                "Outlines boxes only in the loaded file in cyan, and boxes only in the \
+                comparison file in dashed magenta. A box that moved or changed size shows \
+                both.",
+            );
+            if ui.button("Close comparison").clicked() {
+                self.close_comparison();
             }
-            Character::Cammy => {
-                let action_name: action_names::CammyActions =
-                    num::FromPrimitive::from_i32(action_index).unwrap_or_default();
-                match action_name {
-                    action_names::CammyActions::Cammy_INVALID_ID => format!("{}", action_index),
-                    _ => action_name.to_string(),
+        });
+        let action_diff = match &self.action_diff {
+            Some(action_diff) => action_diff,
+            None => return,
+        };
+        if action_diff.missing {
+            ui.label("The comparison file has no action with this ID.");
+            return;
+        }
+        if action_diff.is_empty() {
+            ui.label("No differences.");
+            return;
+        }
+        for change in &action_diff.changes {
+            ui.label(change);
+        }
+        let mut clicked_frame: Option<usize> = None;
+        egui::ScrollArea::vertical()
+            .id_source("Comparison")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                for key_change in &action_diff.key_changes {
+                    if ui.link(diff::key_change_to_string(key_change)).clicked() {
+                        clicked_frame = Some(key_change.first_frame + 1);
+                    }
                 }
-            }
-            Character::Ken => {
-                let action_name: action_names::KenActions =
-                    num::FromPrimitive::from_i32(action_index).unwrap_or_default();
-                match action_name {
-                    action_names::KenActions::Ken_INVALID_ID => format!("{}", action_index),
-                    _ => action_name.to_string(),
+                for box_change in &action_diff.boxes {
+                    let color = match box_change.change {
+                        BoxChangeKind::Removed => DIFF_REMOVED_COLOR,
+                        BoxChangeKind::Added | BoxChangeKind::Changed(_) => DIFF_ADDED_COLOR,
+                    };
+                    let text = RichText::new(diff::box_change_to_string(box_change)).color(color);
+                    if ui.link(text).clicked() {
+                        clicked_frame = Some(box_change.frame + 1);
+                    }
                 }
+            });
+        match clicked_frame {
+            Some(frame) => {
+                self.current_frame = frame.clamp(1, self.selected_frames().max(1));
+                self.should_update = true;
             }
-            Character::DeeJay => {
-                let action_name: action_names::DeeJayActions =
-                    num::FromPrimitive::from_i32(action_index).unwrap_or_default();
-                match action_name {
-                    action_names::DeeJayActions::DeeJay_INVALID_ID => format!("{}", action_index),
-                    _ => action_name.to_string(),
-                }
+            None => (),
+        }
+    }
+
+    /// Stores the current pan and zoom under the open character, so switching back to it can
+    /// pick up where it was.
+    pub fn remember_view(&mut self) {
+        if self.asset.is_none() || self.character_name.is_empty() {
+            return;
+        }
+        self.views.insert(
+            self.character_name.clone(),
+            SavedView {
+                offset_x: self.offset_x,
+                offset_y: self.offset_y,
+                zoom: self.zoom,
+            },
+        );
+    }
+
+    /// Restores the view remembered for the open character, if there is one.
+    pub fn recall_view(&mut self) {
+        match self.views.get(&self.character_name) {
+            Some(view) => {
+                self.offset_x = view.offset_x;
+                self.offset_y = view.offset_y;
+                self.zoom = view.zoom;
+                self.keep_view = true;
             }
-            Character::Lily => {
-                let action_name: action_names::LilyActions =
-                    num::FromPrimitive::from_i32(action_index).unwrap_or_default();
-                match action_name {
-                    action_names::LilyActions::Lily_INVALID_ID => format!("{}", action_index),
-                    _ => action_name.to_string(),
+            None => self.keep_view = false,
+        }
+    }
+
+    /// Puts the origin back at its default spot at 1x zoom.
+    fn reset_view(&mut self) {
+        self.offset_x = self.default_offset_x;
+        self.offset_y = self.default_offset_y;
+        self.zoom = 1.0;
+    }
+
+    fn render_view_settings(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("view_values").show(ui, |ui| {
+            ui.label("Pan");
+            ui.add(egui::DragValue::new(&mut self.offset_x).prefix("x: "));
+            ui.add(egui::DragValue::new(&mut self.offset_y).prefix("y: "));
+            ui.end_row();
+            ui.label("Zoom");
+            ui.add(
+                egui::DragValue::new(&mut self.zoom)
+                    .speed(0.01)
+                    .clamp_range(0.1..=10.0)
+                    .suffix("x"),
+            );
+            ui.end_row();
+        })
+        .response
+        .on_hover_text("Screen position of the origin, in points, and points per game unit.");
+        if ui
+            .button("Reset view")
+            .on_hover_text("Go back to the default origin at 1x zoom. Home does the same.")
+            .clicked()
+        {
+            self.reset_view();
+        }
+        if ui
+            .button("Center on character")
+            .on_hover_text("Pan so the character's current position is in the middle of the canvas.")
+            .clicked()
+        {
+            self.center_on_character();
+        }
+        ui.separator();
+        ui.label("Default origin").on_hover_text(
+            "Where the character's origin goes on screen when a character is opened for the \
+            first time, or the view is reset with Home.",
+        );
+        ui.horizontal(|ui| {
+            ui.add(egui::DragValue::new(&mut self.default_origin[0]).prefix("x: "));
+            ui.add(egui::DragValue::new(&mut self.default_origin[1]).prefix("y: "));
+        });
+        ui.separator();
+        egui::Grid::new("box_outline").show(ui, |ui| {
+            ui.label("Box corners");
+            ui.add(
+                egui::DragValue::new(&mut self.box_rounding)
+                    .speed(0.1)
+                    .clamp_range(0.0..=10.0)
+                    .suffix(" px"),
+            );
+            ui.end_row();
+            ui.label("Box outlines");
+            ui.add(
+                egui::DragValue::new(&mut self.box_stroke_width)
+                    .speed(0.1)
+                    .clamp_range(0.5..=5.0)
+                    .suffix(" px"),
+            );
+            ui.end_row();
+        })
+        .response
+        .on_hover_text("Rounder corners and thicker outlines can read better in exported images.");
+        ui.separator();
+        let filter_text = match self.timeline_kind_filter {
+            0 => "Any hit".to_string(),
+            bit => format!("Kind flag {:#x}", bit),
+        };
+        // The kind flag bits don't have verified names, so only the ones the selected action
+        // uses are offered, by value.
+        let kind_bits = self
+            .timeline
+            .iter()
+            .fold(0, |bits, cell| bits | cell.attack_kind_flag);
+        ComboBox::from_label("Timeline hits")
+            .selected_text(filter_text)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.timeline_kind_filter, 0, "Any hit");
+                for bit in (0..32).map(|shift| 1u32 << shift) {
+                    if kind_bits & bit > 0 {
+                        let label = format!("Kind flag {:#x}", bit);
+                        ui.selectable_value(&mut self.timeline_kind_filter, bit, label);
+                    }
                 }
-            }
-            Character::Blanka => {
-                let action_name: action_names::BlankaActions =
-                    num::FromPrimitive::from_i32(action_index).unwrap_or_default();
-                match action_name {
-                    action_names::BlankaActions::Blanka_INVALID_ID => format!("{}", action_index),
-                    _ => action_name.to_string(),
+            })
+            .response
+            .on_hover_text(
+                "Only mark frames red on the timeline when an attack on them has this kind flag \
+                bit. The list has the bits the selected action's attacks use.",
+            );
+        ui.separator();
+        if ui.button("Forget remembered views").clicked() {
+            self.views.clear();
+        }
+    }
+
+    pub fn selected_index(&self) -> i32 {
+        self.selected_index
+    }
+
+    pub fn current_frame(&self) -> usize {
+        self.current_frame
+    }
+
+    /// ID of the selected action, or -1 if none is selected.
+    pub fn action_id(&self) -> i32 {
+        if self.selected_index == -1 {
+            return -1;
+        }
+        self.action_index
+    }
+
+    /// Re-selects a previously saved action. If the index no longer exists in the loaded asset
+    /// (e.g. the file changed since it was saved), nothing is selected.
+    pub fn restore_selection(&mut self, selected_index: i32, current_frame: usize) {
+        match &self.asset {
+            Some(fchar) => {
+                if selected_index < 0 || selected_index as usize >= fchar.action_list.len() {
+                    self.selected_index = -1;
+                    return;
                 }
+                let action = &fchar.action_list[selected_index as usize];
+                let frames = action.info.action_data.frames.max(0) as usize;
+                self.selected_index = selected_index;
+                self.action_index = action.info.action_data.action_id.clone();
+                self.current_frame = current_frame.clamp(1, frames.max(1));
+                self.should_update = true;
             }
-            Character::Juri => {
-                let action_name: action_names::JuriActions =
-                    num::FromPrimitive::from_i32(action_index).unwrap_or_default();
-                match action_name {
-                    action_names::JuriActions::Juri_INVALID_ID => format!("{}", action_index),
-                    _ => action_name.to_string(),
+            None => self.selected_index = -1,
+        }
+    }
+
+    /// Startup counts the first active frame itself, as the game's frame data does.
+    fn startup(&self) -> Option<i32> {
+        extract::startup(&self.action_info)
+    }
+
+    /// Frames between the last active frame and the first actionable frame.
+    fn recovery(&self) -> Option<i32> {
+        extract::recovery(&self.action_info, &self.active_windows)
+    }
+
+    /// The frame data headline on one line, e.g. "12f startup / 3f active / 18f recovery / 5f
+    /// invuln (immune 0x07, f1-5)". Actions without active frames only get their length.
+    fn frame_summary(&self) -> String {
+        let mut parts: Vec<String> = vec![];
+        if self.active_windows.is_empty() {
+            parts.push(format!("{}f total, no active frames", self.timeline.len()));
+        } else {
+            parts.push(match self.startup() {
+                Some(startup) => format!("{}f startup", startup),
+                None => "startup N/A".to_string(),
+            });
+            let active: Vec<String> = self
+                .active_windows
+                .iter()
+                .map(|(start, end)| (end - start + 1).to_string())
+                .collect();
+            parts.push(format!("{}f active", active.join("+")));
+            parts.push(match self.recovery() {
+                Some(recovery) => format!("{}f recovery", recovery),
+                None => "recovery N/A".to_string(),
+            });
+        }
+        // Runs of frames with the same invulnerability, as displayed frames.
+        let mut runs: Vec<(u8, usize, usize)> = vec![];
+        for (frame, cell) in self.timeline.iter().enumerate() {
+            match runs.last_mut() {
+                Some((immune, _, last)) if *immune == cell.immune && *last == frame => {
+                    *last = frame + 1
                 }
+                _ => runs.push((cell.immune, frame + 1, frame + 1)),
             }
-            Character::Marisa => {
-                let action_name: action_names::MarisaActions =
-                    num::FromPrimitive::from_i32(action_index).unwrap_or_default();
-                match action_name {
-                    action_names::MarisaActions::Marisa_INVALID_ID => format!("{}", action_index),
-                    _ => action_name.to_string(),
-                }
+        }
+        for (immune, first, last) in runs {
+            if immune != 0 {
+                parts.push(format!(
+                    "{}f invuln (immune {:#04x}, f{}-{})",
+                    last - first + 1,
+                    immune,
+                    first,
+                    last
+                ));
             }
-            Character::Guile => {
-                let action_name: action_names::GuileActions =
-                    num::FromPrimitive::from_i32(action_index).unwrap_or_default();
-                match action_name {
-                    action_names::GuileActions::Guile_INVALID_ID => format!("{}", action_index),
-                    _ => action_name.to_string(),
+        }
+        parts.join(" / ")
+    }
+
+    /// Frame advantage if the last active window hits an opponent who is then stunned for
+    /// `stun` frames: the frame they can act again minus the frame this character can.
+    fn frame_advantage(&self, stun: i32) -> Option<i32> {
+        self.active_windows
+            .last()
+            .map(|(start, _)| *start as i32 + stun - self.action_info.end_frame)
+    }
+
+    /// Hit data of the first attack key that has some on the last active window's first frame.
+    fn last_hit_data(&self) -> Option<&HitData> {
+        let (start, _) = self.active_windows.last()?;
+        self.frame_cache
+            .get(start - 1)?
+            .attack_collision_keys
+            .iter()
+            .find_map(|attack_collision_key| attack_collision_key.hit_data.as_ref())
+    }
+
+    fn render_frame_advantage(&mut self, ui: &mut egui::Ui) {
+        ui.label("Frame advantage").on_hover_text(
+            "Compares when the opponent recovers from the stun of the last active window's hit \
+            with this action's first actionable frame. Hitstop and pushback aren't taken into \
+            account.",
+        );
+        match self.last_hit_data() {
+            Some(hit_data) => {
+                for reaction in &hit_data.reactions {
+                    match self.frame_advantage(reaction.time as i32) {
+                        Some(advantage) => {
+                            ui.label(format!(
+                                "Reaction #{}: {:+} ({} frames of stun)",
+                                reaction.situation, advantage, reaction.time
+                            ));
+                        }
+                        None => (),
+                    }
                 }
-            }
-            Character::EHonda => {
-                let action_name: action_names::EHondaActions =
-                    num::FromPrimitive::from_i32(action_index).unwrap_or_default();
-                match action_name {
-                    action_names::EHondaActions::EHonda_INVALID_ID => format!("{}", action_index),
-                    _ => action_name.to_string(),
+                if hit_data.reactions.is_empty() {
+                    ui.label("The hit data has no reactions.");
                 }
             }
-            Character::Jamie => {
-                let action_name: action_names::JamieActions =
-                    num::FromPrimitive::from_i32(action_index).unwrap_or_default();
-                match action_name {
-                    action_names::JamieActions::Jamie_INVALID_ID => format!("{}", action_index),
-                    _ => action_name.to_string(),
-                }
+            None => {
+                ui.label("No hit data on the last active frame.");
             }
         }
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.override_stun, "Custom stun")
+                .on_hover_text("Try a specific hitstun or blockstun instead.");
+            ui.add_enabled(
+                self.override_stun,
+                egui::DragValue::new(&mut self.custom_stun).clamp_range(0..=200),
+            );
+            if self.override_stun {
+                match self.frame_advantage(self.custom_stun) {
+                    Some(advantage) => ui.label(format!("{:+}", advantage)),
+                    None => ui.label("N/A"),
+                };
+            }
+        });
+    }
+
+    fn get_action_name(&self, action_index: i32) -> String {
+        extract::action_name(self.character, action_index)
     }
 
     pub fn right_panel(&mut self, ui: &mut egui::Ui) -> egui::Response {
@@ -445,22 +1271,118 @@ impl Viewer {
             egui::ScrollArea::vertical()
             .auto_shrink([false,false])
             .show(ui, |ui| {
+                if self.compare_asset.is_some() {
+                    ui.collapsing("Comparison", |ui| {
+                        self.render_comparison(ui);
+                    });
+                }
+                ui.collapsing("Character stats", |ui| {
+                    self.render_character_stats(ui);
+                })
+                .header_response
+                .on_hover_text(
+                    "Walk, dash and jump numbers come from running those actions through the \
+                    movement sim on their own, so anything that depends on how they chain into \
+                    each other isn't included.",
+                );
                 ui.collapsing("Motion info", |ui| {
-                    let mut position: String = format!(
-                        "Current Position: {}, {}",
-                        self.position.x,self.position.y
+                    ui.label(format!("Frame {}", self.current_frame)).on_hover_text(
+                        "The sim state entering this frame: the position the boxes are drawn at, \
+                        and the velocity and acceleration carried over from earlier frames, \
+                        before this frame's steer keys run.",
                     );
-                    ui.label(position);
-                    let mut velocity: String = format!(
-                        "Current Velocity: {}, {}",
-                        self.velocity.x,self.velocity.y
-                    );
-                    ui.label(velocity);
-                    let mut acceleration: String = format!(
-                        "Current Acceleration: {}, {}",
-                        self.acceleration.x,self.acceleration.y
-                    );
-                    ui.label(acceleration);
+                    egui::Grid::new("motion_values").striped(true).show(ui, |ui| {
+                        ui.label("");
+                        ui.label("X");
+                        ui.label("Y");
+                        ui.label("Z");
+                        ui.label("Unit");
+                        ui.end_row();
+                        let rows = [
+                            ("Position", &self.position, "units"),
+                            ("Velocity", &self.velocity, "units/frame"),
+                            ("Acceleration", &self.acceleration, "units/frame²"),
+                            ("Root motion", &self.root_motion, "units"),
+                        ];
+                        for (name, value, unit) in rows {
+                            ui.label(name);
+                            ui.monospace(format!("{:.3}", value.x));
+                            ui.monospace(format!("{:.3}", value.y));
+                            ui.monospace(format!("{:.3}", value.z));
+                            ui.label(unit);
+                            ui.end_row();
+                        }
+                    });
+                    if ui
+                        .add(
+                            Slider::new(&mut self.target_distance, 0.0..=1000.0)
+                                .text("Target distance"),
+                        )
+                        .on_hover_text(
+                            "Where homing moves steer towards, measured from the origin. How \
+                            homing keys steer is a guess: the sim reads the homing time as the \
+                            frames left to reach the target and the homing value as a speed \
+                            cap, which hasn't been checked against the game.",
+                        )
+                        .changed()
+                    {
+                        self.invalidate_motion();
+                    }
+                    if ui
+                        .add(Slider::new(&mut self.ground_y, -200.0..=200.0).text("Ground height"))
+                        .on_hover_text(
+                            "The floor the sim starts on and lands back onto, drawn as the brown \
+                            line.",
+                        )
+                        .changed()
+                    {
+                        self.invalidate_motion();
+                    }
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut self.gravity)
+                                .speed(0.01)
+                                .clamp_range(-5.0..=5.0)
+                                .prefix("Gravity: ")
+                                .suffix(" units/frame²"),
+                        )
+                        .on_hover_text(
+                            "Extra downward acceleration while airborne. The game doesn't add any: \
+                            jump arcs come from each action's SteerKeys, so leave this at 0 to \
+                            match it. It adds on top of the keyed acceleration, and SteerKeys \
+                            that set or inherit the acceleration don't change it.",
+                        )
+                        .changed()
+                    {
+                        self.invalidate_motion();
+                    }
+                    ui.horizontal(|ui| {
+                        let mut changed = ui
+                            .checkbox(&mut self.use_wall, "Wall")
+                            .on_hover_text(
+                                "Stop the character's pushbox at a wall, to see how far a move \
+                                travels near the corner. A negative distance puts the wall \
+                                behind the character.",
+                            )
+                            .changed();
+                        changed |= ui
+                            .add_enabled(
+                                self.use_wall,
+                                Slider::new(&mut self.wall_x, -1000.0..=1000.0).text("Distance"),
+                            )
+                            .changed();
+                        if changed {
+                            self.invalidate_motion();
+                        }
+                    });
+                    if self.use_wall {
+                        ui.label(if self.action_wall_pinned {
+                            "The action ends pinned to the wall."
+                        } else {
+                            "The action ends away from the wall."
+                        });
+                    }
+                    self.render_steer_overrides(ui);
                 });
                 ui.collapsing("Action info", |ui| {
                     let mut first_active_frame: String = format!(
@@ -470,24 +1392,148 @@ impl Viewer {
                     if self.action_info.first_active_frame == -1 {
                         first_active_frame = "First active frame: N/A".to_owned();
                     }
-                    ui.label(first_active_frame);
+                    ui.label(first_active_frame).on_hover_text(
+                        "Startup: the first frame that can hit, counting the first frame of the \
+                        action as frame 1. Read straight from the action's frame data.",
+                    );
                     let mut recovery_frame: String =
                         format!("Recovery frame: {}", self.action_info.recovery_frame + 1);
                     if self.action_info.recovery_frame == -1 {
                         recovery_frame = "Recovery frame: N/A".to_owned();
                     }
-                    ui.label(recovery_frame);
+                    ui.label(recovery_frame).on_hover_text(
+                        "The first frame after the active frames. Everything between the first \
+                        active frame and this one is active; from here until the first actionable \
+                        frame is recovery.",
+                    );
                     ui.label(format!(
                         "First actionable frame: {}",
                         self.action_info.end_frame + 1
-                    ));
+                    ))
+                    .on_hover_text(
+                        "The first frame where the character can act again. Frame advantage on \
+                        hit or block compares this against the opponent's hitstun or blockstun; \
+                        hitstop and pushback are not taken into account here, so numbers may \
+                        differ from other frame data sources.",
+                    );
+                    ui.horizontal(|ui| {
+                        let frames = self.frame_cache.len().max(1);
+                        let mut jump_to: Option<usize> = None;
+                        if ui
+                            .add_enabled(
+                                self.action_info.first_active_frame != -1,
+                                egui::Button::new("First active"),
+                            )
+                            .on_hover_text("Jump to the first active frame.")
+                            .clicked()
+                        {
+                            jump_to = Some(self.action_info.first_active_frame as usize + 1);
+                        }
+                        if ui
+                            .add_enabled(
+                                self.action_info.recovery_frame != -1,
+                                egui::Button::new("Recovery"),
+                            )
+                            .on_hover_text("Jump to the first recovery frame.")
+                            .clicked()
+                        {
+                            jump_to = Some(self.action_info.recovery_frame as usize + 1);
+                        }
+                        if ui
+                            .button("Last frame")
+                            .on_hover_text("Jump to the last frame of the action.")
+                            .clicked()
+                        {
+                            jump_to = Some(frames);
+                        }
+                        if let Some(frame) = jump_to {
+                            self.current_frame = frame.clamp(1, frames);
+                            self.should_update = true;
+                        }
+                    });
+                    ui.separator();
+                    let startup: String = match self.startup() {
+                        Some(startup) => format!("Startup: {}", startup),
+                        None => "Startup: N/A".to_owned(),
+                    };
+                    ui.label(startup).on_hover_text(
+                        "Startup counts up to and including the first active frame, the same way \
+                        the game's own frame data does.",
+                    );
+                    let mut active: String = "Active: N/A".to_owned();
+                    if !self.active_windows.is_empty() {
+                        let windows: Vec<String> = self
+                            .active_windows
+                            .iter()
+                            .map(|(start, end)| format!("{} ({}-{})", end - start + 1, start, end))
+                            .collect();
+                        active = format!("Active: {}", windows.join(", "));
+                    }
+                    ui.label(active).on_hover_text(
+                        "Active frames are the frames with at least one attack box, not counting \
+                        proximity guard boxes. Moves that stop hitting partway through list each \
+                        active window separately as count (first-last).",
+                    );
+                    self.render_hit_windows(ui);
+                    match self.action_reach {
+                        Some(reach) => {
+                            ui.label(format!(
+                                "Reach: {:.1} on frame {}, covers {:.1} to {:.1} high",
+                                reach.forward, reach.frame, reach.low, reach.high
+                            ))
+                            .on_hover_text(
+                                "How far forward the character's attack boxes get over the \
+                                whole action, from where it starts, and the lowest and highest \
+                                they cover from the ground. Movement from the sim is included; \
+                                projectiles and proximity boxes aren't.",
+                            );
+                        }
+                        None => (),
+                    }
+                    let recovery: String = match self.recovery() {
+                        Some(recovery) => format!("Recovery: {}", recovery),
+                        None => "Recovery: N/A".to_owned(),
+                    };
+                    ui.label(recovery).on_hover_text(
+                        "Recovery is the number of frames between the last active frame and the \
+                        first actionable frame. Landing recovery and hitstop aren't included.",
+                    );
+                    match self.action_landing_frame {
+                        Some(landing_frame) => {
+                            ui.label(format!("Lands on frame {}", landing_frame)).on_hover_text(
+                                "The first frame the sim has the character back on the ground \
+                                after being airborne.",
+                            );
+                        }
+                        None => (),
+                    }
+                    if self.action_inherits_motion {
+                        ui.label("Inherits motion").on_hover_text(
+                            "The action keeps the velocity or acceleration it starts with. The \
+                            sim starts an action at rest unless it was reached by clicking a \
+                            branch or cancel, so it can travel differently in game.",
+                        );
+                    }
+                    ui.separator();
+                    self.render_frame_advantage(ui);
+                    ui.separator();
+                    self.render_branches(ui);
+                    ui.separator();
+                    self.render_motion_clips(ui);
+                    ui.separator();
                     let mut loop_count: String =
                         format!("Loop count: {}", self.action_info.loop_count);
                     if self.action_info.loop_count == -1 {
                         loop_count = "Loop count: infinite".to_owned();
                     }
-                    ui.label(loop_count);
+                    ui.label(loop_count).on_hover_text(
+                        "How many times the action repeats before ending. Looping actions (walks, \
+                        holds, charges) have no fixed recovery. The file doesn't say where the \
+                        loop starts or ends, so playback ignores it.",
+                    );
                 });
+                ui.collapsing("Bookmarks", |ui| self.render_bookmarks(ui));
+                ui.collapsing("Objects", |ui| self.render_objects(ui));
                 
                 if !self.projectile_keys.is_empty() {
                     ui.collapsing("Projectile info", |ui| {
@@ -507,84 +1553,6 @@ impl Viewer {
                     });
                 }
                 
-                ui.collapsing("Cancel list", |ui| {
-                    for trigger in &self.triggers {
-                        ui.horizontal(|ui| {
-                            ui.label(format!("Action {}", self.get_action_name(trigger.action)));
-                            let mut cancel_flags: String = "".to_owned();
-                            if trigger.condition_flag & 0b1 > 0 {
-                                cancel_flags.push_str("Hit | ")
-                            }
-                            if trigger.condition_flag & 0b10 > 0 {
-                                cancel_flags.push_str("Guard | ")
-                            }
-                            if trigger.condition_flag & 0b100 > 0 {
-                                cancel_flags.push_str("Whiff | ")
-                            }
-                            if trigger.condition_flag & 0b010000000000 > 0 {
-                                cancel_flags.push_str("Counter | ")
-                            }
-                            if trigger.condition_flag & 0b0001000000000000 > 0 {
-                                cancel_flags.push_str("Parry | ")
-                            }
-                            if trigger.condition_flag & 0b0010000000000000 > 0 {
-                                cancel_flags.push_str("Just | ")
-                            }
-                            if trigger.condition_flag & 0b100000000000 > 0 {
-                                cancel_flags.push_str("Strike | ")
-                            }
-                            if trigger.condition_flag & 0b1000 > 0 {
-                                cancel_flags.push_str("Armor | ")
-                            }
-                            if trigger.condition_flag & 0b00010000 > 0 {
-                                cancel_flags.push_str("Jump | ")
-                            }
-                            if trigger.condition_flag & 0b00100000 > 0 {
-                                cancel_flags.push_str("SuperJump | ")
-                            }
-                            if trigger.condition_flag & 0b10000000 > 0 {
-                                cancel_flags.push_str("Fly | ")
-                            }
-                            if trigger.condition_flag & 0b000100000000 > 0 {
-                                cancel_flags.push_str("WallBk | ")
-                            }
-                            if trigger.condition_flag & 0b01000000000000000000 > 0 {
-                                cancel_flags.push_str("VJump | ")
-                            }
-                            if trigger.condition_flag & 0b10000000000000000000 > 0 {
-                                cancel_flags.push_str("FJump | ")
-                            }
-                            if trigger.condition_flag & 0b000100000000000000000000 > 0 {
-                                cancel_flags.push_str("BJump | ")
-                            }
-                            if trigger.condition_flag & 0b001000000000000000000000 > 0 {
-                                cancel_flags.push_str("Throw | ")
-                            }
-                            if trigger.condition_flag & 0b0100000000000000 > 0 {
-                                cancel_flags.push_str("Normal | ")
-                            }
-                            if trigger.condition_flag & 0b1000000000000000 > 0 {
-                                cancel_flags.push_str("Easy | ")
-                            }
-                            if trigger.condition_flag & 0b00010000000000000000 > 0 {
-                                cancel_flags.push_str("Extra | ")
-                            }
-                            if trigger.condition_flag & 0b01000000 > 0 {
-                                cancel_flags.push_str("Defer | ")
-                            }
-                            if trigger.condition_flag & 0b00100000000000000000 > 0 {
-                                cancel_flags.push_str("Inhibit | ")
-                            }
-                            if trigger.condition_flag & 0b010000000000000000000000 > 0 {
-                                cancel_flags.push_str("Terminator | ")
-                            }
-                            if cancel_flags.len() > 3 {
-                                cancel_flags = cancel_flags[0..cancel_flags.len() - 3].to_owned();
-                            }
-                            ui.label(format!("Cancel flags: {}", cancel_flags));
-                        });
-                    }
-                });
             });
 
             ui.horizontal(|ui| {
@@ -607,114 +1575,355 @@ impl Viewer {
         if self.selected_index == -1 {
             action_label = "Select an action".to_owned();
         }
-        ui.label("Filter by name");
-        let textedit_response = ui.add(egui::TextEdit::singleline(&mut self.action_name_filter_string));
-        self.action_name_filter_string = self.action_name_filter_string.to_lowercase();
-        ComboBox::from_label("Action List")
-            .selected_text(action_label)
-            .width(300.0)
-            .show_ui(ui, |ui| match &self.asset {
+        ui.label(action_label);
+        ui.label("Search by name or action index");
+        let textedit_response = ui.add(egui::TextEdit::singleline(&mut self.action_search_string));
+        ui.checkbox(&mut self.only_attacks, "Only actions with hitboxes")
+            .on_hover_text("Hide actions without any attack boxes, not counting proximity boxes.");
+        let query = self.action_search_string.to_lowercase();
+        let query_index = query.parse::<i32>().ok();
+        egui::ScrollArea::vertical()
+            .id_source("Action List")
+            .max_height(200.0)
+            .auto_shrink([false, true])
+            .show(ui, |ui| match &self.asset {
                 Some(fchar) => {
+                    let mut favorites = self
+                        .favorites
+                        .get(&self.character_name)
+                        .cloned()
+                        .unwrap_or_default();
+                    let mut clicked: Option<usize> = None;
+                    let mut toggled: Option<i32> = None;
+                    if !favorites.is_empty() {
+                        ui.label(RichText::new("Favorites").strong());
+                        for index in &favorites {
+                            let action = match fchar.action_list.get(*index as usize) {
+                                Some(action) => action,
+                                None => continue,
+                            };
+                            let action_index = action.info.action_data.action_id;
+                            ui.horizontal(|ui| {
+                                if ui.small_button("★").on_hover_text("Unpin").clicked() {
+                                    toggled = Some(*index);
+                                }
+                                if ui
+                                    .selectable_label(
+                                        self.selected_index == *index,
+                                        format!(
+                                            "Action #{}: {}, Index {}",
+                                            index,
+                                            self.get_action_name(action_index),
+                                            action_index,
+                                        ),
+                                    )
+                                    .clicked()
+                                {
+                                    clicked = Some(*index as usize);
+                                }
+                            });
+                        }
+                        ui.separator();
+                    }
                     for (index, action) in fchar.action_list.iter().enumerate() {
-                        let action_index = &action.info.action_data.action_id;
-                        let filter_string = &self.action_name_filter_string;
-                        if filter_string.is_empty()|| self.get_action_name(*action_index).to_lowercase().contains(filter_string){
-                            if ui 
+                        if self.only_attacks && self.action_has_attacks.get(index) == Some(&false) {
+                            continue;
+                        }
+                        let action_index = action.info.action_data.action_id.clone();
+                        let action_name = self.get_action_name(action_index);
+                        if !query.is_empty()
+                            && !action_name.to_lowercase().contains(&query)
+                            && query_index != Some(action_index)
+                        {
+                            continue;
+                        }
+                        ui.horizontal(|ui| {
+                            let favorite = favorites.contains(&(index as i32));
+                            if ui
+                                .small_button(if favorite { "★" } else { "☆" })
+                                .on_hover_text(if favorite { "Unpin" } else { "Pin to favorites" })
+                                .clicked()
+                            {
+                                toggled = Some(index as i32);
+                            }
+                            if ui
                                 .selectable_label(
-                                    true,
+                                    self.selected_index == index as i32,
                                     format!(
                                         "Action #{}: {}, Index {}",
-                                        index,
-                                        self.get_action_name(action_index.clone()),
-                                        action_index,
+                                        index, action_name, action_index,
                                     ),
                                 )
                                 .clicked()
                             {
-                                self.selected_index = index as i32;
-                                self.action_index = action_index.clone();
-                                self.should_update = true;
-                                self.current_frame = 1;
-                                self.action_index_string = "".to_string();
+                                clicked = Some(index);
+                            }
+                        });
+                    }
+                    match clicked {
+                        Some(index) => {
+                            self.selected_index = index as i32;
+                            self.action_index = fchar.action_list[index].info.action_data.action_id;
+                            self.should_update = true;
+                            self.current_frame = 1;
+                        }
+                        None => (),
+                    }
+                    match toggled {
+                        Some(index) => {
+                            match favorites.iter().position(|favorite| *favorite == index) {
+                                Some(position) => {
+                                    favorites.remove(position);
+                                }
+                                None => favorites.push(index),
                             }
+                            self.favorites.insert(self.character_name.clone(), favorites);
                         }
+                        None => (),
                     }
                 }
                 None => (),
             });
-        ui.label("Search by action index");
-        let textedit_response = ui.add(egui::TextEdit::singleline(&mut self.action_index_string));
+        // A numeric query jumps straight to the action with that index on Enter.
         if textedit_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-            match &self.asset {
-                Some(fchar) => {
-                    for (index, action) in fchar.action_list.iter().enumerate() {
-                        let action_index = action.info.action_data.action_id.clone();
-                        if self.action_index_string != "" {
-                            let action_string = self.action_index_string.parse::<i32>();
-                            match action_string {
-                                Ok(parsed_action_index) => {
-                                    if parsed_action_index == action_index {
-                                        self.selected_index = index as i32;
-                                        self.action_index = action_index.clone();
-                                        self.should_update = true;
-                                        self.current_frame = 1;
-                                    }
-                                }
-                                Err(_) => (),
-                            }
-                        }
+            match (&self.asset, query_index) {
+                (Some(fchar), Some(parsed_action_index)) => {
+                    let matches: Vec<usize> = fchar
+                        .action_list
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, action)| {
+                            action.info.action_data.action_id == parsed_action_index
+                        })
+                        .map(|(index, _)| index)
+                        .collect();
+                    if matches.len() == 1 {
+                        self.selected_index = matches[0] as i32;
+                        self.action_index = parsed_action_index;
+                        self.should_update = true;
+                        self.current_frame = 1;
+                        self.search_matches.clear();
+                    } else {
+                        self.search_matches = matches;
+                    }
+                    self.action_search_string = "".to_string();
+                }
+                _ => (),
+            }
+        }
+        // Some files have several actions with the same index, so let the user pick one instead
+        // of jumping to whichever comes last.
+        if self.search_matches.len() > 1 {
+            let mut selected: Option<usize> = None;
+            let mut dismissed = false;
+            ui.horizontal_wrapped(|ui| {
+                ui.label(format!(
+                    "{} actions have index {}:",
+                    self.search_matches.len(),
+                    self.action_index_at(self.search_matches[0])
+                ));
+                for index in &self.search_matches {
+                    if ui
+                        .selectable_label(
+                            self.selected_index == *index as i32,
+                            format!("Action #{}", index),
+                        )
+                        .clicked()
+                    {
+                        selected = Some(*index);
                     }
                 }
+                dismissed = ui.small_button("✖").clicked();
+            });
+            match selected {
+                Some(index) => {
+                    self.selected_index = index as i32;
+                    self.action_index = self.action_index_at(index);
+                    self.should_update = true;
+                    self.current_frame = 1;
+                }
                 None => (),
             }
-            self.action_index_string = "".to_string();
+            if dismissed {
+                self.search_matches.clear();
+            }
         }
 
         if self.selected_index != -1 {
+            // Don't steal keys from the search box or any other text field.
+            if !ui.ctx().wants_keyboard_input() {
+                self.handle_keyboard(ui);
+            }
+            self.record_history();
+            // egui can't build a frame slider over an empty range, and there is nothing to
+            // simulate, so stop before any of that.
+            if self.selected_frames() == 0 {
+                if self.should_update {
+                    self.action_info = Default::default();
+                    self.timeline.clear();
+                    self.branches.clear();
+                    self.motion_clips.clear();
+                    self.active_windows.clear();
+                    self.cancel_windows.clear();
+                    self.hit_windows.clear();
+                    self.frame_cache.clear();
+                    self.frame_velocities.clear();
+                    self.frame_positions.clear();
+                    self.original_positions.clear();
+                    self.frame_motion.clear();
+                    self.action_bounds = None;
+                    self.action_reach = None;
+                    self.action_landing_frame = None;
+                    self.action_inherits_motion = false;
+                    self.reset_motion();
+                    self.load_cached_frame();
+                    self.projectile_keys.clear();
+                    self.cached_index = self.selected_index;
+                    self.should_update = false;
+                }
+                self.playing = false;
+                return ui
+                    .horizontal(|ui| {
+                        ui.label("This action has no frames.");
+                    })
+                    .response;
+            }
+            if let Some(frame) = self.gif_capture_frame {
+                // Capturing only works while the canvas is on screen.
+                self.tab = ViewerTab::Boxes;
+                self.playing = false;
+                if self.current_frame != frame {
+                    self.current_frame = frame;
+                    self.should_update = true;
+                }
+                self.gif_frame_drawn = true;
+                ui.ctx().request_repaint();
+            }
             if self.should_update {
                 self.action_info = Default::default();
                 self.get_action_info();
-                self.position = Default::default();
-                self.velocity = Default::default();
-                self.acceleration = Default::default();
-                self.prev_position = Default::default();
-                self.prev_velocity = Default::default();
-                self.prev_acceleration = Default::default();
-                self.root_motion = Default::default();
-                for frame in 0..self.current_frame - 1 {
-                    self.update_position(frame as i32);
-                }
-                self.get_boxes();
-                self.get_trigger_keys();
+                if self.cached_index != self.selected_index {
+                    self.get_timeline();
+                    self.get_active_windows();
+                    self.get_cancel_windows();
+                    self.get_frame_cache();
+                    self.hit_windows = extract::get_hit_windows(&self.frame_cache);
+                    self.get_branches();
+                    self.get_motion_clips();
+                    self.frame_motion.clear();
+                    self.steer_overrides = Default::default();
+                    self.cached_index = self.selected_index;
+                    self.fit_pending = !self.keep_view;
+                    self.keep_view = false;
+                }
+                if self.frame_motion.len() != self.frame_cache.len() {
+                    self.get_frame_velocities();
+                }
+                match self.frame_motion.get(self.current_frame - 1) {
+                    Some(motion_state) => {
+                        let motion_state = motion_state.clone();
+                        self.set_motion_state(motion_state);
+                    }
+                    None => self.reset_motion(),
+                }
+                self.update_trail();
+                self.load_cached_frame();
                 self.get_projectile_keys();
                 self.should_update = false;
             }
+            if self.compare_asset.is_some() && self.diff_index != self.selected_index {
+                self.get_action_diff();
+            }
+            ui.label(RichText::new(self.frame_summary()).strong());
             ui.horizontal(|ui| match &self.asset {
                 Some(fchar) => {
                     let action = &fchar.action_list[self.selected_index.clone() as usize];
+                    let frames = action.info.action_data.frames.max(0) as usize;
                     let temp_frame = self.current_frame;
-                    ui.spacing_mut().slider_width = ui.available_width() - 150f32;
+                    let play_label = if self.playing { "Pause" } else { "Play" };
+                    if ui.button(play_label).clicked() {
+                        self.playing = !self.playing;
+                        self.playback_timer = 0.0;
+                        self.playing_backwards = false;
+                        if self.playing && self.current_frame >= frames {
+                            self.current_frame = 1;
+                        }
+                    }
+                    // The step buttons step when pressed and keep stepping while held.
+                    let step_back = ui
+                        .add_enabled(self.current_frame > 1, egui::Button::new("⏴"))
+                        .on_hover_text("Previous frame (left arrow or ,). Hold to keep stepping.")
+                        .is_pointer_button_down_on();
+                    let step_forward = ui
+                        .add_enabled(self.current_frame < frames, egui::Button::new("⏵"))
+                        .on_hover_text("Next frame (right arrow or .). Hold to keep stepping.")
+                        .is_pointer_button_down_on();
+                    for _ in 0..self.held_steps(ui, step_back || step_forward) {
+                        self.current_frame = self.step_target(step_forward, frames);
+                    }
+                    ui.spacing_mut().slider_width = ui.available_width() - 420f32;
                     ui.add(
                         Slider::new(
                             &mut self.current_frame,
-                            1..=action.info.action_data.frames as usize,
+                            1..=frames,
                         )
                             .clamp_to_range(true)
                             .smart_aim(true)
-                            .orientation(egui::SliderOrientation::Horizontal)
-                            .text("Current Frame"),
+                            .show_value(false)
+                            .orientation(egui::SliderOrientation::Horizontal),
                     );
-                    if ui.input(|i| i.key_pressed(egui::Key::ArrowLeft)) {
-                        self.current_frame -= 1;
-                        if self.current_frame < 1 {
-                            self.current_frame = 1;
-                        }
-                    }
-                    if ui.input(|i| i.key_pressed(egui::Key::ArrowRight)) {
-                        self.current_frame += 1;
-                        if self.current_frame > action.info.action_data.frames as usize {
-                            self.current_frame = action.info.action_data.frames as usize;
+                    // Click the number to type a frame in, e.g. one quoted from a guide.
+                    ui.add(
+                        egui::DragValue::new(&mut self.current_frame)
+                            .clamp_range(1..=frames)
+                            .speed(0.25)
+                            .suffix(format!(" / {}", frames)),
+                    )
+                    .on_hover_text("Current frame. Click to type a frame number.");
+                    ComboBox::from_label("Speed")
+                        .selected_text(format!("{}x", self.playback_speed))
+                        .width(60.0)
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.playback_speed, 0.25, "0.25x");
+                            ui.selectable_value(&mut self.playback_speed, 0.5, "0.5x");
+                            ui.selectable_value(&mut self.playback_speed, 1.0, "1x");
+                        });
+                    let modes = [
+                        (PlaybackMode::Once, "Once"),
+                        (PlaybackMode::Loop, "Loop"),
+                        (PlaybackMode::PingPong, "Ping-pong"),
+                    ];
+                    let mode_label = match modes
+                        .iter()
+                        .find(|(mode, _)| *mode == self.playback_mode)
+                    {
+                        Some((_, label)) => *label,
+                        None => "",
+                    };
+                    ComboBox::from_label("Mode")
+                        .selected_text(mode_label)
+                        .width(80.0)
+                        .show_ui(ui, |ui| {
+                            for (mode, label) in modes {
+                                ui.selectable_value(&mut self.playback_mode, mode, label);
+                            }
+                        })
+                        .response
+                        .on_hover_text(
+                            "What playback does at the end of the action. Once stops on the last \
+                            frame; Loop and Ping-pong repeat the whole action.",
+                        );
+                    if self.playing {
+                        // The game runs at 60fps, so one frame of the action is 1/60th of a second at 1x.
+                        self.playback_timer += ui.input(|i| i.stable_dt) * self.playback_speed;
+                        while self.playback_timer >= 1f32 / 60f32 {
+                            self.playback_timer -= 1f32 / 60f32;
+                            if !self.advance_playback(frames) {
+                                self.playing = false;
+                                break;
+                            }
                         }
+                        ui.ctx().request_repaint();
                     }
                     if temp_frame != self.current_frame {
                         self.should_update = true;
@@ -722,9 +1931,46 @@ impl Viewer {
                 }
                 None => (),
             });
-            egui::ScrollArea::vertical().show(ui, |ui| {
-                Frame::canvas(ui.style()).show(ui, |ui| self.render_boxes(ui));
+            self.render_timeline(ui);
+            ui.horizontal(|ui| {
+                for (tab, label) in [
+                    (ViewerTab::Boxes, "Boxes"),
+                    (ViewerTab::FrameData, "Frame data"),
+                    (ViewerTab::Cancels, "Cancels"),
+                    (ViewerTab::RawKeys, "Raw keys"),
+                ] {
+                    ui.selectable_value(&mut self.tab, tab, label);
+                }
             });
+            ui.separator();
+            match self.tab {
+                ViewerTab::Boxes => {
+                    self.render_toolbar(ui);
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        Frame::canvas(ui.style()).show(ui, |ui| self.render_boxes(ui));
+                    });
+                }
+                ViewerTab::FrameData => {
+                    egui::ScrollArea::both()
+                        .id_source("Frame data tab")
+                        .show(ui, |ui| self.render_frame_table(ui));
+                }
+                ViewerTab::Cancels => {
+                    egui::ScrollArea::vertical()
+                        .id_source("Cancels tab")
+                        .show(ui, |ui| self.render_cancel_list(ui));
+                }
+                ViewerTab::RawKeys => {
+                    egui::ScrollArea::vertical()
+                        .id_source("Raw keys tab")
+                        .show(ui, |ui| self.render_raw_keys(ui));
+                }
+            }
+            // Changes made after the frame was refreshed above, e.g. by the keyboard or a click on
+            // the timeline, only show up on the next update, which egui won't run on its own.
+            if self.should_update {
+                ui.ctx().request_repaint();
+            }
 
             ui.horizontal(|ui| {
                 ui.label("");
@@ -738,6 +1984,631 @@ impl Viewer {
         }
     }
 
+    /// What the action can cancel into on the current frame, grouped by kind of trigger.
+    fn render_cancel_list(&mut self, ui: &mut egui::Ui) {
+        if self.triggers.is_empty() {
+            ui.label("Nothing to cancel into on this frame.");
+        } else {
+            ui.label(RichText::new("Open on this frame").strong());
+        }
+        let mut followed: Option<i32> = None;
+        for (group_name, triggers) in group_triggers(&self.triggers) {
+            if triggers.is_empty() {
+                continue;
+            }
+            egui::CollapsingHeader::new(format!("{} ({})", group_name, triggers.len()))
+                .id_source(group_name)
+                .default_open(true)
+                .show(ui, |ui| {
+                    for trigger in &triggers {
+                        ui.horizontal(|ui| {
+                            if ui
+                                .link(format!("Action {}", self.get_action_name(trigger.action)))
+                                .on_hover_text(
+                                    "Cancel into this action, carrying over the motion after \
+                                    this frame.",
+                                )
+                                .clicked()
+                            {
+                                followed = Some(trigger.action);
+                            }
+                            ui.label(
+                                RichText::new(self.cancel_window_text(trigger.action)).weak(),
+                            );
+                            ui.label(format!(
+                                "Cancel flags: {}",
+                                cancel_flags_to_string(trigger.condition_flag)
+                            ));
+                            match &trigger.charge {
+                                Some(charge) => {
+                                    ui.label(format!(
+                                        "Requires {}f charge (lever {:#04x})",
+                                        charge.frames, charge.lever
+                                    ))
+                                    .on_hover_text(format!(
+                                        "The charge is kept for {} frames after \
+                                        letting go.",
+                                        charge.keep_frames
+                                    ));
+                                }
+                                None => (),
+                            }
+                        });
+                    }
+                });
+        }
+        if let Some(action_id) = followed {
+            let motion_state = self.motion_after(self.current_frame);
+            self.follow_action(action_id, motion_state);
+        }
+        let mut closed: Vec<i32> = vec![];
+        for cancel_window in &self.cancel_windows {
+            if !closed.contains(&cancel_window.action)
+                && !self
+                    .triggers
+                    .iter()
+                    .any(|trigger| trigger.action == cancel_window.action)
+            {
+                closed.push(cancel_window.action);
+            }
+        }
+        if !closed.is_empty() {
+            egui::CollapsingHeader::new(format!("Not open on this frame ({})", closed.len()))
+                .id_source("Closed cancels")
+                .show(ui, |ui| {
+                    for action in closed {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("Action {}", self.get_action_name(action)));
+                            ui.label(RichText::new(self.cancel_window_text(action)).weak());
+                        });
+                    }
+                });
+        }
+        self.render_cancel_sources(ui);
+    }
+
+    /// Every action that can cancel into this one, with the frames of that action it's open on.
+    /// Clicking one goes to the first of those frames.
+    fn render_cancel_sources(&mut self, ui: &mut egui::Ui) {
+        let sources: Vec<CancelWindow> = match &self.cancel_sources {
+            Some(cancel_sources) => match cancel_sources.get(&self.action_id()) {
+                Some(sources) => sources.clone(),
+                None => vec![],
+            },
+            None => vec![],
+        };
+        let mut source_actions: Vec<i32> = sources.iter().map(|source| source.action).collect();
+        source_actions.dedup();
+        let mut clicked: Option<(i32, usize)> = None;
+        ui.separator();
+        egui::CollapsingHeader::new(format!("Cancels from ({})", source_actions.len()))
+            .id_source("Cancel sources")
+            .default_open(true)
+            .show(ui, |ui| {
+                if source_actions.is_empty() {
+                    ui.label("No action cancels into this one.");
+                }
+                for action in &source_actions {
+                    let windows: Vec<&CancelWindow> = sources
+                        .iter()
+                        .filter(|source| source.action == *action)
+                        .collect();
+                    ui.horizontal(|ui| {
+                        if ui
+                            .link(format!("Action {}", self.get_action_name(*action)))
+                            .on_hover_text("Go to the first frame it can cancel into this action.")
+                            .clicked()
+                        {
+                            clicked = Some((*action, windows[0].first_frame));
+                        }
+                        ui.label(RichText::new(cancel_windows_text(&windows)).weak());
+                    });
+                }
+            });
+        match clicked {
+            Some((action, frame)) => {
+                if self.select_action_id(action) {
+                    self.current_frame = frame.clamp(1, self.selected_frames().max(1));
+                }
+            }
+            None => (),
+        }
+    }
+
+    /// Export buttons, view settings and overlay toggles shown above the canvas.
+    fn render_toolbar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            #[cfg(not(target_arch = "wasm32"))]
+            if ui.button("Export JSON").clicked() {
+                self.export_json();
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            if ui
+                .button("Export CSV")
+                .on_hover_text("Save a frame meter with one row per frame, for spreadsheets.")
+                .clicked()
+            {
+                self.export_csv();
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            if ui
+                .button("Export PNG")
+                .on_hover_text("Save the canvas as an image, labelled with the action and frame.")
+                .clicked()
+            {
+                self.png_export_requested = true;
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            if ui
+                .add_enabled(self.gif_capture_frame.is_none(), egui::Button::new("Export GIF"))
+                .on_hover_text("Save every frame of the action as an animated GIF.")
+                .clicked()
+            {
+                // Capturing starts on the next update, once frame 1 is drawn.
+                self.gif_capture_frame = Some(1);
+                self.gif_restore_frame = self.current_frame;
+                ui.ctx().request_repaint();
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            ui.add(
+                egui::DragValue::new(&mut self.gif_fps)
+                    .clamp_range(1..=50)
+                    .suffix(" fps"),
+            )
+            .on_hover_text("Frame rate of exported GIFs. The game runs at 60.");
+            if ui
+                .button("Copy frame")
+                .on_hover_text("Copy this frame's boxes to the clipboard as text.")
+                .clicked()
+            {
+                let text = self.frame_to_string();
+                ui.output_mut(|o| o.copied_text = text);
+            }
+            if ui
+                .button("Fit")
+                .on_hover_text("Zoom and pan so every box in the action is in view.")
+                .clicked()
+            {
+                self.fit_pending = true;
+            }
+            ui.checkbox(&mut self.follow_character, "Follow").on_hover_text(
+                "Keep the character in the middle of the canvas on every frame, so moves that \
+                travel don't leave the view. Dragging can't pan while this is on.",
+            );
+            ui.menu_button("Colors", |ui| self.box_colors.ui(ui));
+            ui.menu_button("View", |ui| self.render_view_settings(ui));
+            ui.checkbox(&mut self.facing_left, "Face left")
+                .on_hover_text("Mirror the boxes and movement around the origin.");
+            ui.checkbox(&mut self.show_box_values, "Show box values").on_hover_text(
+                "Label each box with its centre and size in game units, and attack boxes \
+                with their hit ID.",
+            );
+            ui.checkbox(&mut self.show_trajectory, "Show trajectory").on_hover_text(
+                "Plot where the character is on every frame of the action.",
+            );
+            ui.checkbox(&mut self.show_trail, "Show trail").on_hover_text(
+                "Leave fading dots where the character was on the last few frames played. \
+                Jumping to another frame starts the trail over.",
+            );
+            if self.show_trail {
+                ui.add(
+                    egui::DragValue::new(&mut self.trail_length)
+                        .clamp_range(1..=120)
+                        .suffix(" frames"),
+                );
+            }
+            ui.checkbox(&mut self.show_disjoint, "Show disjoint").on_hover_text(
+                "Highlight the parts of the character's hitboxes that stick out past all of \
+                their hurtboxes, where the attack can hit without being hit back.",
+            );
+            ui.checkbox(&mut self.show_all_hitboxes, "Show all hitboxes").on_hover_text(
+                "Faintly draw the hitboxes of every other frame of the action where the \
+                character was on that frame, to see the move's total coverage.",
+            );
+            ui.checkbox(&mut self.skip_unchanged, "Skip unchanged frames").on_hover_text(
+                "Step straight to the next frame where the boxes or cancels change, and back \
+                to the first frame of the previous change.",
+            );
+            ui.checkbox(&mut self.show_dummy, "Show dummy").on_hover_text(
+                "Stand an opponent pushbox at the target distance. Drag it to move it, and \
+                any hitbox touching it is outlined.",
+            );
+            ui.checkbox(&mut self.show_ghost_frame, "Show ghost frame")
+                .on_hover_text("Draw the previous frame's boxes faintly behind this one.");
+            if ui
+                .toggle_value(&mut self.measuring, "Measure")
+                .on_hover_text("Left-drag in the viewport to measure instead of panning.")
+                .changed()
+            {
+                self.measure_start = None;
+                self.measure_end = None;
+            }
+            if !self.missing_boxes.is_empty() {
+                let missing: Vec<String> = self
+                    .missing_boxes
+                    .iter()
+                    .map(|missing_box| format!("{} #{}", missing_box.table, missing_box.index))
+                    .collect();
+                ui.label(RichText::new("⚠").color(Color32::YELLOW)).on_hover_text(format!(
+                    "Keys on this frame reference boxes that aren't in the file:\n{}",
+                    missing.join("\n")
+                ));
+            }
+        });
+    }
+
+    /// Frame count of the selected action, or 0 if there's none or the file reports a
+    /// negative count.
+    fn selected_frames(&self) -> usize {
+        match &self.asset {
+            Some(fchar) => match fchar.action_list.get(self.selected_index as usize) {
+                Some(action) => action.info.action_data.frames.max(0) as usize,
+                None => 0,
+            },
+            None => 0,
+        }
+    }
+
+    fn handle_keyboard(&mut self, ui: &egui::Ui) {
+        let (step_back, step_forward, previous_action, next_action, reset_view) = ui.input(|i| {
+            // egui has no key codes for comma and period, so look at the typed text instead.
+            let typed = |text: &str| {
+                i.events
+                    .iter()
+                    .any(|event| matches!(event, egui::Event::Text(typed) if typed == text))
+            };
+            (
+                i.key_pressed(egui::Key::ArrowLeft) || typed(","),
+                i.key_pressed(egui::Key::ArrowRight) || typed("."),
+                i.key_pressed(egui::Key::PageUp),
+                i.key_pressed(egui::Key::PageDown),
+                i.key_pressed(egui::Key::Home),
+            )
+        });
+        if reset_view {
+            self.reset_view();
+        }
+        let (undo, redo) = ui.input(|i| {
+            let z = i.key_pressed(egui::Key::Z) && i.modifiers.command;
+            (
+                z && !i.modifiers.shift,
+                (i.key_pressed(egui::Key::Y) && i.modifiers.command) || (z && i.modifiers.shift),
+            )
+        });
+        if undo || redo {
+            self.step_history(redo);
+            return;
+        }
+        match &self.asset {
+            Some(fchar) => {
+                let action = &fchar.action_list[self.selected_index.clone() as usize];
+                let frames = action.info.action_data.frames.max(0) as usize;
+                if step_back && self.current_frame > 1 {
+                    self.current_frame = self.step_target(false, frames);
+                    self.should_update = true;
+                }
+                if step_forward && self.current_frame < frames {
+                    self.current_frame = self.step_target(true, frames);
+                    self.should_update = true;
+                }
+                let mut selected_index = self.selected_index;
+                if previous_action && selected_index > 0 {
+                    selected_index -= 1;
+                }
+                if next_action && ((selected_index + 1) as usize) < fchar.action_list.len() {
+                    selected_index += 1;
+                }
+                if selected_index != self.selected_index {
+                    self.selected_index = selected_index;
+                    self.action_index = fchar.action_list[selected_index as usize]
+                        .info
+                        .action_data
+                        .action_id
+                        .clone();
+                    self.should_update = true;
+                    self.current_frame = 1;
+                }
+            }
+            None => (),
+        }
+    }
+
+    fn history_entry(&self) -> HistoryEntry {
+        HistoryEntry {
+            character_name: self.character_name.clone(),
+            selected_index: self.selected_index,
+            frame: self.current_frame,
+            offset_x: self.offset_x,
+            offset_y: self.offset_y,
+            zoom: self.zoom,
+        }
+    }
+
+    /// Adds the current place to the history if it's a jump from the current entry: another
+    /// character or action, or a frame more than one step away while paused. Anything else
+    /// just updates the current entry. Going somewhere new drops the entries Ctrl+Y would
+    /// have gone forward to.
+    fn record_history(&mut self) {
+        if self.history_pending {
+            return;
+        }
+        let entry = self.history_entry();
+        let jumped = match self.history.get(self.history_cursor) {
+            Some(current) => {
+                current.character_name != entry.character_name
+                    || current.selected_index != entry.selected_index
+                    || (!self.playing && current.frame.abs_diff(entry.frame) > 1)
+            }
+            None => true,
+        };
+        if !jumped {
+            self.history[self.history_cursor] = entry;
+            return;
+        }
+        if !self.history.is_empty() {
+            self.history.truncate(self.history_cursor + 1);
+        }
+        self.history.push(entry);
+        if self.history.len() > HISTORY_LENGTH {
+            self.history.remove(0);
+        }
+        self.history_cursor = self.history.len() - 1;
+    }
+
+    /// Moves one entry back through the history, or forward if `forward`.
+    fn step_history(&mut self, forward: bool) {
+        let cursor = if forward {
+            self.history_cursor + 1
+        } else {
+            match self.history_cursor.checked_sub(1) {
+                Some(cursor) => cursor,
+                None => return,
+            }
+        };
+        if cursor >= self.history.len() {
+            return;
+        }
+        self.history_cursor = cursor;
+        if self.history[cursor].character_name != self.character_name {
+            self.history_pending = true;
+            return;
+        }
+        self.apply_history();
+    }
+
+    /// The character an undo or redo wants open. Once the app has opened it, it calls
+    /// `apply_history` to finish the jump.
+    pub fn take_history_character(&mut self) -> Option<String> {
+        if !self.history_pending {
+            return None;
+        }
+        self.history_pending = false;
+        self.history
+            .get(self.history_cursor)
+            .map(|entry| entry.character_name.clone())
+    }
+
+    /// Goes to the current history entry.
+    pub fn apply_history(&mut self) {
+        let entry = match self.history.get(self.history_cursor) {
+            Some(entry) => entry.clone(),
+            None => return,
+        };
+        self.restore_selection(entry.selected_index, entry.frame);
+        self.playing = false;
+        self.offset_x = entry.offset_x;
+        self.offset_y = entry.offset_y;
+        self.zoom = entry.zoom;
+        self.keep_view = true;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_json(&self) {
+        if self.frame_cache.is_empty() {
+            return;
+        }
+        let path = rfd::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .set_file_name(&format!("{}.json", self.get_action_name(self.action_index)))
+            .save_file();
+        let path = match path {
+            Some(path) => path,
+            None => return,
+        };
+
+        let export: Vec<FrameExport<'_>> = self
+            .frame_cache
+            .iter()
+            .enumerate()
+            .map(|(index, frame_boxes)| FrameExport {
+                frame: index + 1,
+                push_collision_keys: &frame_boxes.push_collision_keys,
+                damage_collision_keys: &frame_boxes.damage_collision_keys,
+                attack_collision_keys: &frame_boxes.attack_collision_keys,
+            })
+            .collect();
+
+        match serde_json::to_string_pretty(&export) {
+            Ok(json) => {
+                if let Err(err) = std::fs::write(&path, json) {
+                    log::error!("Failed to write {}: {}", path.display(), err);
+                }
+            }
+            Err(err) => log::error!("Failed to serialize box data: {}", err),
+        }
+    }
+
+    /// Writes one row per frame from the frame cache and the recorded sim states. Velocities are
+    /// the ones each frame starts with, and the pushbox width is the character's own full width.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_csv(&self) {
+        if self.frame_cache.is_empty() {
+            return;
+        }
+        let path = rfd::FileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .set_file_name(&format!(
+                "{} - {}.csv",
+                self.character_name,
+                self.get_action_name(self.action_index)
+            ))
+            .save_file();
+        let path = match path {
+            Some(path) => path,
+            None => return,
+        };
+
+        let mut csv = "frame,has_hitbox,has_proximity,hurtbox_count,pushbox_width,vel_x,vel_y,\
+            cancel_window_open\n"
+            .to_string();
+        for (index, frame_boxes) in self.frame_cache.iter().enumerate() {
+            let has_hitbox = frame_boxes
+                .attack_collision_keys
+                .iter()
+                .any(|key| !key.is_proximity() && !key.boxes.is_empty());
+            let has_proximity = frame_boxes
+                .attack_collision_keys
+                .iter()
+                .any(|key| key.is_proximity() && !key.boxes.is_empty());
+            let hurtbox_count: usize = frame_boxes
+                .damage_collision_keys
+                .iter()
+                .map(|key| key.hurtboxes().count())
+                .sum();
+            let pushbox_width = match frame_boxes
+                .push_collision_keys
+                .iter()
+                .find(|key| key.object_id == 0)
+            {
+                Some(key) => format!("{}", key.pushbox.width * 2.0),
+                None => "".to_string(),
+            };
+            let (vel_x, vel_y) = self.frame_velocities.get(index).cloned().unwrap_or_default();
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{}\n",
+                index + 1,
+                has_hitbox,
+                has_proximity,
+                hurtbox_count,
+                pushbox_width,
+                vel_x,
+                vel_y,
+                !frame_boxes.triggers.is_empty()
+            ));
+        }
+        if let Err(err) = std::fs::write(&path, csv) {
+            log::error!("Failed to write {}: {}", path.display(), err);
+        }
+    }
+
+    /// One line naming the character, action and frame, for exports.
+    fn frame_label(&self) -> String {
+        format!(
+            "{} - {} (#{}), frame {}/{}",
+            self.character_name,
+            self.get_action_name(self.action_index),
+            self.action_index,
+            self.current_frame,
+            self.frame_cache.len()
+        )
+    }
+
+    /// Hands a pending PNG export to the app, which has to capture the screen: the canvas rect
+    /// to crop to, and a file name for it.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn take_png_export(&mut self) -> Option<(Rect, String)> {
+        if !self.png_export_requested {
+            return None;
+        }
+        self.png_export_requested = false;
+        Some((
+            self.canvas_rect,
+            format!(
+                "{}_frame{}.png",
+                self.get_action_name(self.action_index),
+                self.current_frame
+            ),
+        ))
+    }
+
+    /// Hands the frame just drawn to the app while a GIF export is running, then moves on to
+    /// the next one. Each frame of the action is shown for one update, so the export takes as
+    /// many updates as the action has frames.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn take_gif_frame(&mut self) -> Option<GifFrame> {
+        if !std::mem::take(&mut self.gif_frame_drawn) {
+            return None;
+        }
+        let frame = self.gif_capture_frame?;
+        let frames = self.frame_cache.len();
+        let last = frame >= frames;
+        if last {
+            self.gif_capture_frame = None;
+            self.current_frame = self.gif_restore_frame.clamp(1, frames.max(1));
+            self.should_update = true;
+        } else {
+            self.gif_capture_frame = Some(frame + 1);
+        }
+        Some(GifFrame {
+            rect: self.canvas_rect,
+            last,
+            file_name: format!("{}.gif", self.get_action_name(self.action_index)),
+            fps: self.gif_fps,
+        })
+    }
+
+    /// Formats the current frame's boxes as a readable block of text.
+    fn frame_to_string(&self) -> String {
+        let mut lines: Vec<String> = vec![self.frame_label()];
+        for push_collision_key in &self.push_collision_keys {
+            lines.push(format!(
+                "Pushbox (object {}, attribute {}): {}",
+                push_collision_key.object_id,
+                push_collision_key.attribute,
+                box_values(&push_collision_key.pushbox)
+            ));
+        }
+        for damage_collision_key in &self.damage_collision_keys {
+            for (region, boxes) in damage_collision_key.regions() {
+                for collision_box in boxes {
+                    lines.push(format!(
+                        "{} hurtbox (object {}, level {}, immune {}, type flag {:#x}): {}",
+                        region,
+                        damage_collision_key.object_id,
+                        damage_collision_key.level,
+                        damage_collision_key.immune,
+                        damage_collision_key.type_flag,
+                        box_values(collision_box)
+                    ));
+                }
+            }
+            for collision_box in &damage_collision_key.throw_boxes {
+                lines.push(format!(
+                    "Throw hurtbox (object {}): {}",
+                    damage_collision_key.object_id,
+                    box_values(collision_box)
+                ));
+            }
+        }
+        for attack_collision_key in &self.attack_collision_keys {
+            for collision_box in &attack_collision_key.boxes {
+                lines.push(format!(
+                    "Hitbox (object {}, type {}, hit ID {}, guard {}, kind flag {:#x}): {}",
+                    attack_collision_key.object_id,
+                    attack_collision_key.collision_type,
+                    attack_collision_key.hit_id,
+                    attack_collision_key.guard_requirement(),
+                    attack_collision_key.kind_flag,
+                    box_values(&offset_box(collision_box, attack_collision_key.hit_offset))
+                ));
+            }
+            if let Some(hit_data) = &attack_collision_key.hit_data {
+                lines.push(hit_data_to_string(hit_data));
+            }
+        }
+        lines.join("\n")
+    }
+
     fn get_projectile_keys(&mut self)
     {
         self.projectile_keys.clear();
@@ -746,9 +2617,12 @@ impl Viewer {
                 let action = &fchar.action_list[self.selected_index.clone() as usize];
                 for object in &action.objects {
                     for (index, object_index) in object.action.object_table.iter().enumerate() {
-                        if object.info.object_data.key_data[index].key_start_frame <= self.current_frame as i32 - 1
-                            && object.info.object_data.key_data[index].key_end_frame > self.current_frame as i32 - 1
-                        {
+                        let key_data = &object.info.object_data.key_data[index];
+                        if extract::key_active(
+                            key_data.key_start_frame,
+                            key_data.key_end_frame,
+                            extract::key_frame(self.current_frame),
+                        ) {
                             let data = &object.action.data[object_index.clone() as usize - 1];
                             match data.name.as_str() {
                                 "CharacterAsset.ShotKey" => {
@@ -852,50 +2726,965 @@ impl Viewer {
     fn get_action_info(&mut self) {
         match &self.asset {
             Some(fchar) => {
-                let action = &fchar.action_list[self.selected_index.clone() as usize];
-                let action_frame = &action.action.data[0];
-                let first_active_frame = &action_frame.fields[0].value;
-                match first_active_frame {
-                    RSZValue::Int32(frame) => self.action_info.first_active_frame = frame.clone(),
-                    _ => (),
-                }
-                let recovery_frame = &action_frame.fields[1].value;
-                match recovery_frame {
-                    RSZValue::Int32(frame) => self.action_info.recovery_frame = frame.clone(),
-                    _ => (),
-                }
-                let end_frame = &action_frame.fields[2].value;
-                match end_frame {
-                    RSZValue::Int32(frame) => self.action_info.end_frame = frame.clone(),
-                    _ => (),
-                }
-                let action_state = &action.action.data[1];
-                let loop_count = &action_state.fields[0].value;
-                match loop_count {
-                    RSZValue::Int32(count) => self.action_info.loop_count = count.clone(),
-                    _ => (),
-                }
+                self.action_info =
+                    extract::get_action_info(fchar, self.selected_index.clone() as usize)
             }
             None => (),
         }
     }
 
-    fn update_position(&mut self, frame: i32) {
-        self.position.x += self.velocity.x;
+    /// Resolves the boxes and cancels of every frame of the selected action up front, so that
+    /// moving between frames is just a lookup.
+    fn get_frame_cache(&mut self) {
+        match &self.asset {
+            Some(fchar) => {
+                self.frame_cache =
+                    extract::get_frames(fchar, self.selected_index.clone() as usize)
+            }
+            None => self.frame_cache.clear(),
+        }
+    }
+
+    fn get_branches(&mut self) {
+        match &self.asset {
+            Some(fchar) => {
+                self.branches = extract::get_branches(fchar, self.selected_index.clone() as usize)
+            }
+            None => self.branches.clear(),
+        }
+    }
+
+    fn get_motion_clips(&mut self) {
+        match &self.asset {
+            Some(fchar) => {
+                self.motion_clips =
+                    extract::get_motion_clips(fchar, self.selected_index.clone() as usize)
+            }
+            None => self.motion_clips.clear(),
+        }
+    }
+
+    /// Every field of every key active on the current frame, for working out what the fields
+    /// nothing decodes yet are for.
+    fn render_raw_keys(&mut self, ui: &mut egui::Ui) {
+        let fchar = match &self.asset {
+            Some(fchar) => fchar,
+            None => return,
+        };
+        let frame = (self.selected_index, self.current_frame);
+        if self.raw_keys_frame != Some(frame) {
+            self.raw_keys = extract::get_raw_keys(
+                fchar,
+                self.selected_index as usize,
+                extract::key_frame(self.current_frame),
+            );
+            self.raw_keys_frame = Some(frame);
+        }
+        if self.raw_keys.is_empty() {
+            ui.label("No keys on this frame");
+        }
+        for (index, raw_key) in self.raw_keys.iter().enumerate() {
+            let mut title = format!(
+                "{} (frames {}-{})",
+                raw_key.name.trim_start_matches("CharacterAsset."),
+                raw_key.start_frame + 1,
+                raw_key.end_frame
+            );
+            if raw_key.object_id != 0 {
+                title = format!("{}, object {}", title, raw_key.object_id);
+            }
+            egui::CollapsingHeader::new(title)
+                .id_source(("raw_key", index))
+                .show(ui, |ui| {
+                    egui::Grid::new(("raw_key_fields", index))
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for (name, value) in &raw_key.fields {
+                                ui.label(name);
+                                ui.monospace(value);
+                                ui.end_row();
+                            }
+                        });
+                });
+        }
+    }
+
+    /// The outline color for hitboxes of `hit_id`, picked by the order the action's hit IDs
+    /// first come out in so a hit keeps its color on every frame. None if the action only has
+    /// one hit ID.
+    fn hit_id_color(&self, hit_id: i8) -> Option<Color32> {
+        let mut hit_ids: Vec<i8> = vec![];
+        for hit_window in &self.hit_windows {
+            if !hit_ids.contains(&hit_window.hit_id) {
+                hit_ids.push(hit_window.hit_id);
+            }
+        }
+        if hit_ids.len() < 2 {
+            return None;
+        }
+        hit_ids
+            .iter()
+            .position(|id| *id == hit_id)
+            .map(|rank| HIT_ID_COLORS[rank % HIT_ID_COLORS.len()])
+    }
+
+    /// Moves playback on by one frame as the playback mode says. Returns false once playing
+    /// through just once has reached the last frame.
+    fn advance_playback(&mut self, frames: usize) -> bool {
+        match self.playback_mode {
+            PlaybackMode::Once => {
+                if self.current_frame < frames {
+                    self.current_frame += 1;
+                    true
+                } else {
+                    false
+                }
+            }
+            PlaybackMode::Loop => {
+                if self.current_frame < frames {
+                    self.current_frame += 1;
+                } else {
+                    self.current_frame = 1;
+                }
+                true
+            }
+            PlaybackMode::PingPong => {
+                if self.playing_backwards && self.current_frame <= 1 {
+                    self.playing_backwards = false;
+                } else if !self.playing_backwards && self.current_frame >= frames {
+                    self.playing_backwards = true;
+                }
+                if self.playing_backwards {
+                    self.current_frame = (self.current_frame - 1).max(1);
+                } else {
+                    self.current_frame = (self.current_frame + 1).min(frames);
+                }
+                true
+            }
+        }
+    }
+
+    /// How many frames a held step button should step this update: one as soon as it's pressed,
+    /// then one every `STEP_REPEAT_INTERVAL` once it's been held for `STEP_REPEAT_DELAY`.
+    fn held_steps(&mut self, ui: &egui::Ui, held: bool) -> usize {
+        if !held {
+            self.step_held = None;
+            return 0;
+        }
+        ui.ctx().request_repaint();
+        match self.step_held {
+            Some((held_for, repeats)) => {
+                let held_for = held_for + ui.input(|i| i.stable_dt);
+                let due = if held_for < STEP_REPEAT_DELAY {
+                    0
+                } else {
+                    ((held_for - STEP_REPEAT_DELAY) / STEP_REPEAT_INTERVAL) as usize + 1
+                };
+                self.step_held = Some((held_for, due));
+                due - repeats
+            }
+            None => {
+                self.step_held = Some((0.0, 0));
+                1
+            }
+        }
+    }
+
+    /// The frame one step back or forward goes to. When skipping unchanged frames, that's the
+    /// first frame of the next or previous run of frames with the same boxes and cancels.
+    fn step_target(&self, forward: bool, frames: usize) -> usize {
+        let index = self.current_frame - 1;
+        if !self.skip_unchanged || self.frame_cache.len() != frames || index >= frames {
+            return if forward {
+                (self.current_frame + 1).min(frames)
+            } else {
+                (self.current_frame - 1).max(1)
+            };
+        }
+        let current = &self.frame_cache[index];
+        if forward {
+            return match (index + 1..frames)
+                .find(|frame| !self.frame_cache[*frame].same_events(current))
+            {
+                Some(frame) => frame + 1,
+                None => frames,
+            };
+        }
+        let mut target = match (0..index)
+            .rev()
+            .find(|frame| !self.frame_cache[*frame].same_events(current))
+        {
+            Some(frame) => frame,
+            None => return 1,
+        };
+        while target > 0 && self.frame_cache[target - 1].same_events(&self.frame_cache[target]) {
+            target -= 1;
+        }
+        target + 1
+    }
+
+    /// Checkbox and value per axis for overriding steer keys. Changing any of them simulates the
+    /// action again.
+    fn render_steer_overrides(&mut self, ui: &mut egui::Ui) {
+        ui.label("Steer overrides").on_hover_text(
+            "Replace the value this action's steer keys set or add on an axis, to see how the \
+            move would travel with it. The path from the file is drawn dashed next to the new \
+            one. Picking another action clears the overrides.",
+        );
+        let names = [
+            "Velocity X",
+            "Velocity Y",
+            "Velocity Z",
+            "Acceleration X",
+            "Acceleration Y",
+            "Acceleration Z",
+        ];
+        egui::Grid::new("steer_overrides").show(ui, |ui| {
+            for (index, name) in names.iter().enumerate() {
+                let mut enabled = self.steer_overrides[index].is_some();
+                let mut value = self.steer_overrides[index].unwrap_or_default();
+                let mut changed = ui.checkbox(&mut enabled, *name).changed();
+                changed |= ui
+                    .add_enabled(enabled, egui::DragValue::new(&mut value).speed(0.01))
+                    .changed();
+                ui.end_row();
+                if changed {
+                    self.steer_overrides[index] = if enabled { Some(value) } else { None };
+                    self.invalidate_motion();
+                }
+            }
+        });
+    }
+
+    /// The action ID of an entry of the action list, or -1 if there's no such entry.
+    fn action_index_at(&self, index: usize) -> i32 {
+        match self
+            .asset
+            .as_ref()
+            .and_then(|fchar| fchar.action_list.get(index))
+        {
+            Some(action) => action.info.action_data.action_id,
+            None => -1,
+        }
+    }
+
+    /// Bookmarks on the selected action of the open character.
+    fn action_bookmarks(&self) -> impl Iterator<Item = &Bookmark> {
+        self.bookmarks.iter().filter(|bookmark| {
+            bookmark.character_name == self.character_name
+                && bookmark.selected_index == self.selected_index
+        })
+    }
+
+    /// A field to bookmark the current frame with, and the open character's bookmarks to jump
+    /// back to.
+    fn render_bookmarks(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.bookmark_label)
+                    .hint_text("Label")
+                    .desired_width(150.0),
+            );
+            if ui
+                .button("Bookmark this frame")
+                .on_hover_text("Bookmarks are marked on the timeline and kept between launches.")
+                .clicked()
+            {
+                self.bookmarks.push(Bookmark {
+                    character_name: self.character_name.clone(),
+                    selected_index: self.selected_index,
+                    frame: self.current_frame,
+                    label: std::mem::take(&mut self.bookmark_label),
+                });
+            }
+        });
+        let mut jump_to: Option<(i32, usize)> = None;
+        let mut removed: Option<usize> = None;
+        for (index, bookmark) in self.bookmarks.iter().enumerate() {
+            if bookmark.character_name != self.character_name {
+                continue;
+            }
+            ui.horizontal(|ui| {
+                let mut text = format!(
+                    "Action #{} ({}), frame {}",
+                    bookmark.selected_index,
+                    self.get_action_name(self.action_index_at(bookmark.selected_index as usize)),
+                    bookmark.frame
+                );
+                if !bookmark.label.is_empty() {
+                    text = format!("{}: {}", text, bookmark.label);
+                }
+                let current = bookmark.selected_index == self.selected_index
+                    && bookmark.frame == self.current_frame;
+                if ui.selectable_label(current, text).clicked() {
+                    jump_to = Some((bookmark.selected_index, bookmark.frame));
+                }
+                if ui.small_button("✖").on_hover_text("Remove").clicked() {
+                    removed = Some(index);
+                }
+            });
+        }
+        match jump_to {
+            Some((selected_index, frame)) => {
+                self.restore_selection(selected_index, frame);
+            }
+            None => (),
+        }
+        match removed {
+            Some(index) => {
+                self.bookmarks.remove(index);
+            }
+            None => (),
+        }
+    }
+
+    /// Selects the action with ID `action_id`, if the file has one.
+    fn select_action_id(&mut self, action_id: i32) -> bool {
+        let index = match &self.asset {
+            Some(fchar) => fchar
+                .action_list
+                .iter()
+                .position(|action| action.info.action_data.action_id == action_id),
+            None => None,
+        };
+        match index {
+            Some(index) => {
+                self.selected_index = index as i32;
+                self.action_index = action_id;
+                self.should_update = true;
+                self.current_frame = 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// One row per hit, with the hit under the current frame highlighted. Clicking a hit's
+    /// frames jumps to its first frame.
+    fn render_hit_windows(&mut self, ui: &mut egui::Ui) {
+        if self.hit_windows.len() < 2 {
+            return;
+        }
+        let mut clicked_frame: Option<usize> = None;
+        let highlight = ui.visuals().warn_fg_color;
+        egui::Grid::new("hit_windows").striped(true).show(ui, |ui| {
+            ui.label("Hit");
+            ui.label("Hit ID");
+            ui.label("Frames");
+            ui.label("Guard");
+            ui.end_row();
+            for (index, hit_window) in self.hit_windows.iter().enumerate() {
+                let current = hit_window.first_frame <= self.current_frame
+                    && self.current_frame <= hit_window.last_frame;
+                let text = |text: String| {
+                    if current {
+                        RichText::new(text).strong().color(highlight)
+                    } else {
+                        RichText::new(text)
+                    }
+                };
+                ui.label(text(format!("{}", index + 1)));
+                match hit_window.object_id {
+                    0 => ui.label(text(format!("{}", hit_window.hit_id))),
+                    object_id => ui.label(text(format!(
+                        "{} (object {})",
+                        hit_window.hit_id, object_id
+                    ))),
+                };
+                if ui
+                    .link(text(format!(
+                        "{}-{}",
+                        hit_window.first_frame, hit_window.last_frame
+                    )))
+                    .clicked()
+                {
+                    clicked_frame = Some(hit_window.first_frame);
+                }
+                ui.label(text(hit_window.guard_requirement.to_string()));
+                ui.end_row();
+            }
+        });
+        match clicked_frame {
+            Some(frame) => {
+                self.current_frame = frame;
+                self.should_update = true;
+            }
+            None => (),
+        }
+    }
+
+    /// Which of the action's objects the boxes on this frame belong to. Object 0 is the
+    /// character; the rest are parts and projectiles the action spawns.
+    fn render_objects(&self, ui: &mut egui::Ui) {
+        let mut counts: Vec<[usize; 3]> = vec![];
+        let mut count = |object_id: usize, kind: usize, boxes: usize| {
+            if counts.len() <= object_id {
+                counts.resize(object_id + 1, [0; 3]);
+            }
+            counts[object_id][kind] += boxes;
+        };
+        for push_collision_key in &self.push_collision_keys {
+            count(push_collision_key.object_id, 0, 1);
+        }
+        for damage_collision_key in &self.damage_collision_keys {
+            count(
+                damage_collision_key.object_id,
+                1,
+                damage_collision_key.hurtboxes().count() + damage_collision_key.throw_boxes.len(),
+            );
+        }
+        for attack_collision_key in &self.attack_collision_keys {
+            count(
+                attack_collision_key.object_id,
+                2,
+                attack_collision_key.boxes.len(),
+            );
+        }
+        if counts.iter().all(|kinds| kinds.iter().all(|boxes| *boxes == 0)) {
+            ui.label("No boxes on this frame.");
+            return;
+        }
+        egui::Grid::new("object_boxes").striped(true).show(ui, |ui| {
+            ui.label("");
+            ui.label("Pushboxes");
+            ui.label("Hurtboxes");
+            ui.label("Hitboxes");
+            ui.end_row();
+            for (object_id, kinds) in counts.iter().enumerate() {
+                if kinds.iter().all(|boxes| *boxes == 0) {
+                    continue;
+                }
+                ui.label(match object_id {
+                    0 => "Character".to_string(),
+                    object_id => format!("Object {}", object_id),
+                });
+                for boxes in kinds {
+                    ui.label(boxes.to_string());
+                }
+                ui.end_row();
+            }
+        });
+    }
+
+    fn render_motion_clips(&self, ui: &mut egui::Ui) {
+        if self.motion_clips.is_empty() {
+            ui.label("Motion: none");
+            return;
+        }
+        ui.label("Motion").on_hover_text(
+            "The animation clips the action plays, by motion bank and ID, and which frames of \
+            the clip play on which frames of the action. Clip names aren't in the file, so only \
+            the IDs are shown.",
+        );
+        for motion_clip in &self.motion_clips {
+            let mut label = format!(
+                "Frames {}-{}: bank {}, motion {}, clip frames {}-{}",
+                motion_clip.start_frame.max(0) + 1,
+                motion_clip.end_frame,
+                motion_clip.motion_type,
+                motion_clip.motion_id,
+                motion_clip.motion_start_frame,
+                motion_clip.motion_end_frame
+            );
+            if motion_clip.object_id != 0 {
+                label = format!("{} (object {})", label, motion_clip.object_id);
+            }
+            let current = extract::key_active(
+                motion_clip.start_frame,
+                motion_clip.end_frame,
+                extract::key_frame(self.current_frame),
+            );
+            ui.label(if current {
+                RichText::new(label).strong()
+            } else {
+                RichText::new(label)
+            });
+        }
+    }
+
+    fn render_branches(&mut self, ui: &mut egui::Ui) {
+        if self.branches.is_empty() {
+            ui.label("Branches: none");
+            return;
+        }
+        ui.label("Branches").on_hover_text(
+            "Actions this one can move on to while a BranchKey is active, such as follow-ups, \
+            stance changes and the transition at its end. Click one to select it, carrying over \
+            the motion after the branch's last frame.",
+        );
+        let mut selected: Option<(i32, i32)> = None;
+        for branch in &self.branches {
+            let label = format!(
+                "Frames {}-{}: → {} (index {}), from frame {}",
+                branch.start_frame.max(0) + 1,
+                branch.end_frame,
+                self.get_action_name(branch.action),
+                branch.action,
+                branch.action_frame + 1
+            );
+            if ui
+                .link(label)
+                .on_hover_text(format!(
+                    "Type {}, trigger {}",
+                    branch.branch_type, branch.trigger_id
+                ))
+                .clicked()
+            {
+                selected = Some((branch.action, branch.end_frame));
+            }
+        }
+        if let Some((action_id, end_frame)) = selected {
+            let motion_state = self.motion_after(end_frame.max(0) as usize);
+            self.follow_action(action_id, motion_state);
+        }
+    }
+
+    fn reset_motion(&mut self) {
+        self.position = Default::default();
+        self.position.y = self.ground_y;
+        self.velocity = Default::default();
+        self.acceleration = Default::default();
+        self.prev_position = Default::default();
+        self.prev_velocity = Default::default();
+        self.prev_acceleration = Default::default();
+        self.root_motion = Default::default();
+        self.object_root_motion.clear();
+        self.homing_time = 0.0;
+        self.homing_value = 0.0;
+        self.landing_frame = None;
+        self.inherits_motion = false;
+        self.wall_pinned = false;
+    }
+
+    /// Resets the sim for a run over the whole action, starting from the motion carried over
+    /// from the previous action if this one was followed to.
+    fn start_motion(&mut self) {
+        self.reset_motion();
+        match &self.carried_motion {
+            Some((index, motion_state)) if *index == self.selected_index => {
+                self.prev_velocity = motion_state.velocity.clone();
+                self.prev_acceleration = motion_state.acceleration.clone();
+            }
+            Some(_) => self.carried_motion = None,
+            None => (),
+        }
+    }
+
+    /// The sim state after displayed frame `frame`, or after the last frame past the end.
+    fn motion_after(&self, frame: usize) -> MotionState {
+        match self.frame_motion.get(frame) {
+            Some(motion_state) => motion_state.clone(),
+            None => self.action_end_motion.clone(),
+        }
+    }
+
+    /// Selects the action with ID `action_id` the way a branch or cancel reaches it, keeping
+    /// the velocity and acceleration of `motion_state` for its SetInherit keys.
+    fn follow_action(&mut self, action_id: i32, motion_state: MotionState) {
+        if self.select_action_id(action_id) {
+            self.carried_motion = Some((self.selected_index, motion_state));
+            self.invalidate_motion();
+        }
+    }
+
+    /// Runs the movement sim over the whole action once, recording the state each frame starts
+    /// with, where the character is, and the bounds of all its boxes. Leaves the sim reset, so
+    /// restore a frame afterwards.
+    fn get_frame_velocities(&mut self) {
+        self.frame_velocities.clear();
+        self.frame_positions.clear();
+        self.frame_motion.clear();
+        self.action_bounds = None;
+        self.action_reach = None;
+        self.start_motion();
+        for frame in 0..self.frame_cache.len() {
+            self.frame_motion.push(self.motion_state());
+            self.frame_velocities.push((self.velocity.x, self.velocity.y));
+            self.frame_positions.push((
+                self.position.x + self.root_motion.x,
+                self.position.y + self.root_motion.y,
+            ));
+            let frame_bounds = self.frame_bounds(&self.frame_cache[frame]);
+            self.action_bounds = match (self.action_bounds, frame_bounds) {
+                (Some(bounds), Some(frame_bounds)) => Some(bounds.union(frame_bounds)),
+                (bounds, frame_bounds) => bounds.or(frame_bounds),
+            };
+            self.update_reach(frame);
+            self.update_position(frame as i32);
+        }
+        self.action_landing_frame = self.landing_frame;
+        self.action_inherits_motion = self.inherits_motion;
+        self.action_wall_pinned = self.wall_pinned;
+        self.action_end_motion = self.motion_state();
+        // Run the action again as the file has it, to draw next to the changed path.
+        self.original_positions.clear();
+        let steer_overrides = std::mem::take(&mut self.steer_overrides);
+        if steer_overrides.iter().any(Option::is_some) {
+            self.start_motion();
+            for frame in 0..self.frame_cache.len() {
+                self.original_positions.push((
+                    self.position.x + self.root_motion.x,
+                    self.position.y + self.root_motion.y,
+                ));
+                self.update_position(frame as i32);
+            }
+        }
+        self.steer_overrides = steer_overrides;
+        self.reset_motion();
+    }
+
+    /// Game-space bounds of one frame's boxes at the current position.
+    fn frame_bounds(&self, frame_boxes: &FrameBoxes) -> Option<Rect> {
+        let mut world_boxes: Vec<Rect> = vec![];
+        for push_collision_key in &frame_boxes.push_collision_keys {
+            world_boxes.push(self.world_box(
+                &push_collision_key.pushbox,
+                true,
+                push_collision_key.object_id,
+            ));
+        }
+        for damage_collision_key in &frame_boxes.damage_collision_keys {
+            for hurtbox in damage_collision_key
+                .hurtboxes()
+                .chain(&damage_collision_key.throw_boxes)
+            {
+                world_boxes.push(self.world_box(hurtbox, true, damage_collision_key.object_id));
+            }
+        }
+        for attack_collision_key in &frame_boxes.attack_collision_keys {
+            for hitbox in &attack_collision_key.boxes {
+                if attack_collision_key.is_proximity() {
+                    world_boxes.push(self.world_box(hitbox, false, attack_collision_key.object_id));
+                } else {
+                    let hitbox = offset_box(hitbox, attack_collision_key.hit_offset);
+                    world_boxes.push(self.world_box(&hitbox, true, attack_collision_key.object_id));
+                }
+            }
+        }
+        world_boxes
+            .into_iter()
+            .reduce(|bounds, world_box| bounds.union(world_box))
+    }
+
+    /// Widens `action_reach` to cover the character's own attack boxes on `frame`, at the
+    /// current position. Proximity boxes and boxes on spawned objects don't count.
+    fn update_reach(&mut self, frame: usize) {
+        for attack_collision_key in &self.frame_cache[frame].attack_collision_keys {
+            if attack_collision_key.is_proximity() || attack_collision_key.object_id != 0 {
+                continue;
+            }
+            for hitbox in &attack_collision_key.boxes {
+                let hitbox = offset_box(hitbox, attack_collision_key.hit_offset);
+                let world_box = self.world_box(&hitbox, true, 0);
+                let low = world_box.min.y - self.ground_y;
+                let high = world_box.max.y - self.ground_y;
+                self.action_reach = match self.action_reach {
+                    Some(reach) => Some(Reach {
+                        forward: reach.forward.max(world_box.max.x),
+                        frame: if world_box.max.x > reach.forward {
+                            frame + 1
+                        } else {
+                            reach.frame
+                        },
+                        low: reach.low.min(low),
+                        high: reach.high.max(high),
+                    }),
+                    None => Some(Reach {
+                        forward: world_box.max.x,
+                        frame: frame + 1,
+                        low,
+                        high,
+                    }),
+                };
+            }
+        }
+    }
+
+    /// Pans so the character's current position, root motion included, is in the middle of the
+    /// canvas.
+    fn center_on_character(&mut self) {
+        let center = self.canvas_rect.center();
+        self.offset_x =
+            center.x - (self.position.x + self.root_motion.x) * self.facing_sign() * self.zoom;
+        self.offset_y = center.y + (self.position.y + self.root_motion.y) * self.zoom;
+    }
+
+    /// Sets the zoom and pan so the whole action's boxes fill `view`, leaving a margin.
+    fn fit_view(&mut self, view: Rect) {
+        let bounds = match self.action_bounds {
+            Some(bounds) => bounds,
+            None => return,
+        };
+        let margin = 40f32;
+        let zoom_x = (view.width() - margin * 2.0) / bounds.width().max(1.0);
+        let zoom_y = (view.height() - margin * 2.0) / bounds.height().max(1.0);
+        self.zoom = zoom_x.min(zoom_y).clamp(0.1, 10.0);
+        let center = bounds.center();
+        self.offset_x = view.center().x - center.x * self.facing_sign() * self.zoom;
+        self.offset_y = view.center().y + center.y * self.zoom;
+    }
+
+    fn motion_state(&self) -> MotionState {
+        MotionState {
+            position: self.position.clone(),
+            velocity: self.velocity.clone(),
+            acceleration: self.acceleration.clone(),
+            prev_position: self.prev_position.clone(),
+            prev_velocity: self.prev_velocity.clone(),
+            prev_acceleration: self.prev_acceleration.clone(),
+            root_motion: self.root_motion.clone(),
+            object_root_motion: self.object_root_motion.clone(),
+            homing_time: self.homing_time,
+            homing_value: self.homing_value,
+            landing_frame: self.landing_frame,
+        }
+    }
+
+    fn set_motion_state(&mut self, motion_state: MotionState) {
+        self.position = motion_state.position;
+        self.velocity = motion_state.velocity;
+        self.acceleration = motion_state.acceleration;
+        self.prev_position = motion_state.prev_position;
+        self.prev_velocity = motion_state.prev_velocity;
+        self.prev_acceleration = motion_state.prev_acceleration;
+        self.root_motion = motion_state.root_motion;
+        self.object_root_motion = motion_state.object_root_motion;
+        self.homing_time = motion_state.homing_time;
+        self.homing_value = motion_state.homing_value;
+        self.landing_frame = motion_state.landing_frame;
+    }
+
+    /// Runs the action with the given ID on its own from a standing start, with no opponent in
+    /// range. Returns None if the file has no such action.
+    fn simulate_movement(&mut self, action_id: i32) -> Option<Movement> {
+        let (index, frames, action_info) = match &self.asset {
+            Some(fchar) => {
+                let index = fchar
+                    .action_list
+                    .iter()
+                    .position(|action| action.info.action_data.action_id == action_id)?;
+                (
+                    index,
+                    fchar.action_list[index].info.action_data.frames.max(0) as usize,
+                    extract::get_action_info(fchar, index),
+                )
+            }
+            None => return None,
+        };
+        let selected_index = self.selected_index;
+        let action_info = std::mem::replace(&mut self.action_info, action_info);
+        let steer_overrides = std::mem::take(&mut self.steer_overrides);
+        let use_wall = std::mem::take(&mut self.use_wall);
+        let gravity = std::mem::take(&mut self.gravity);
+        self.selected_index = index as i32;
+        self.reset_motion();
+        let mut height = 0f32;
+        for frame in 0..frames {
+            self.update_position(frame as i32);
+            height = height.max(self.position.y + self.root_motion.y - self.ground_y);
+        }
+        let movement = Movement {
+            distance: self.position.x + self.root_motion.x,
+            height,
+            frames,
+            landing_frame: self.landing_frame,
+        };
+        self.selected_index = selected_index;
+        self.action_info = action_info;
+        self.steer_overrides = steer_overrides;
+        self.use_wall = use_wall;
+        self.gravity = gravity;
+        self.reset_motion();
+        self.invalidate_motion();
+        Some(movement)
+    }
+
+    fn get_character_stats(&mut self) -> CharacterStats {
+        CharacterStats {
+            personal_data: match &self.asset {
+                Some(fchar) => extract::get_personal_data(fchar),
+                None => None,
+            },
+            walk_forward: self.simulate_movement(CommonActions::Common_WALK_FORWARD as i32),
+            walk_back: self.simulate_movement(CommonActions::Common_WALK_BACK as i32),
+            dash_forward: self.simulate_movement(CommonActions::Common_DASH_F as i32),
+            dash_back: self.simulate_movement(CommonActions::Common_DASH_B as i32),
+            prejump: self.simulate_movement(CommonActions::Common_JUMP_V_BGN as i32),
+            neutral_jump: self.simulate_movement(CommonActions::Common_JUMP_V as i32),
+            forward_jump: self.simulate_movement(CommonActions::Common_JUMP_F as i32),
+            back_jump: self.simulate_movement(CommonActions::Common_JUMP_B as i32),
+        }
+    }
+
+    fn render_character_stats(&self, ui: &mut egui::Ui) {
+        let stats = &self.character_stats;
+        let not_found = "not in this file".to_string();
+        let walk = |movement: Option<Movement>| match movement {
+            Some(movement) if movement.frames > 0 => format!(
+                "{:.3} units/frame",
+                movement.distance.abs() / movement.frames as f32
+            ),
+            _ => not_found.clone(),
+        };
+        let dash = |movement: Option<Movement>| match movement {
+            Some(movement) => format!(
+                "{:.1} units, {} frames",
+                movement.distance.abs(),
+                movement.frames
+            ),
+            None => not_found.clone(),
+        };
+        let jump = |movement: Option<Movement>| match movement {
+            Some(movement) => match movement.landing_frame {
+                Some(landing_frame) => format!(
+                    "{:.1} high, {:.1} across, lands on frame {}",
+                    movement.height,
+                    movement.distance.abs(),
+                    landing_frame
+                ),
+                None => format!(
+                    "{:.1} high, {:.1} across, {} frames",
+                    movement.height,
+                    movement.distance.abs(),
+                    movement.frames
+                ),
+            },
+            None => not_found.clone(),
+        };
+        egui::Grid::new("character_stats").striped(true).show(ui, |ui| {
+            match &stats.personal_data {
+                Some(personal_data) => {
+                    let rows = [
+                        ("Health", personal_data.vitality),
+                        ("Gauge", personal_data.gauge),
+                        ("Weight", personal_data.weight as i32),
+                        ("Jump count", personal_data.multi_jump_count as i32),
+                    ];
+                    for (name, value) in rows {
+                        ui.label(name);
+                        ui.monospace(value.to_string());
+                        ui.end_row();
+                    }
+                }
+                None => {
+                    ui.label("Health");
+                    ui.label(&not_found);
+                    ui.end_row();
+                }
+            }
+            let rows = [
+                ("Forward walk", walk(stats.walk_forward)),
+                ("Back walk", walk(stats.walk_back)),
+                ("Forward dash", dash(stats.dash_forward)),
+                ("Back dash", dash(stats.dash_back)),
+                (
+                    "Prejump",
+                    match stats.prejump {
+                        Some(movement) => format!("{} frames", movement.frames),
+                        None => not_found.clone(),
+                    },
+                ),
+                ("Neutral jump", jump(stats.neutral_jump)),
+                ("Forward jump", jump(stats.forward_jump)),
+                ("Back jump", jump(stats.back_jump)),
+            ];
+            for (name, value) in rows {
+                ui.label(name);
+                ui.monospace(value);
+                ui.end_row();
+            }
+        });
+    }
+
+    /// Throws away the recorded sim states after one of the sim's inputs changed, so the
+    /// action is simulated again on the next update.
+    fn invalidate_motion(&mut self) {
+        self.frame_motion.clear();
+        self.should_update = true;
+    }
+
+    fn load_cached_frame(&mut self) {
+        match self
+            .current_frame
+            .checked_sub(1)
+            .and_then(|index| self.frame_cache.get(index))
+        {
+            Some(frame_boxes) => {
+                self.push_collision_keys = frame_boxes.push_collision_keys.clone();
+                self.damage_collision_keys = frame_boxes.damage_collision_keys.clone();
+                self.attack_collision_keys = frame_boxes.attack_collision_keys.clone();
+                self.triggers = frame_boxes.triggers.clone();
+                self.missing_boxes = frame_boxes.missing_boxes.clone();
+            }
+            None => {
+                self.push_collision_keys.clear();
+                self.damage_collision_keys.clear();
+                self.attack_collision_keys.clear();
+                self.missing_boxes.clear();
+                self.triggers.clear();
+            }
+        }
+    }
+
+    fn get_timeline(&mut self) {
+        match &self.asset {
+            Some(fchar) => {
+                self.timeline = extract::get_timeline(fchar, self.selected_index.clone() as usize)
+            }
+            None => self.timeline.clear(),
+        }
+    }
+
+    fn get_active_windows(&mut self) {
+        self.active_windows = extract::get_active_windows(&self.timeline);
+    }
+
+    fn get_cancel_windows(&mut self) {
+        match &self.asset {
+            Some(fchar) => {
+                self.cancel_windows =
+                    extract::get_cancel_windows(fchar, self.selected_index.clone() as usize)
+            }
+            None => self.cancel_windows.clear(),
+        }
+    }
+
+    /// The frames an action can be cancelled into on, such as "frames 10-14, 20-25".
+    fn cancel_window_text(&self, action: i32) -> String {
+        let windows: Vec<&CancelWindow> = self
+            .cancel_windows
+            .iter()
+            .filter(|cancel_window| cancel_window.action == action)
+            .collect();
+        cancel_windows_text(&windows)
+    }
+
+    fn update_position(&mut self, frame: i32) {
+        let airborne = self.position.y > self.ground_y;
+        let mut ignored = [false; 6];
+        self.position.x += self.velocity.x;
         self.position.y += self.velocity.y;
         self.position.z += self.velocity.z;
         self.velocity.x += self.acceleration.x;
         self.velocity.y += self.acceleration.y;
         self.velocity.z += self.acceleration.z;
+        // Kept out of `acceleration` so that SteerKeys setting or inheriting the acceleration
+        // don't pick it up or wipe it out.
+        if airborne {
+            self.velocity.y -= self.gravity;
+        }
 
         match &self.asset {
             Some(fchar) => {
                 let action = &fchar.action_list[self.selected_index.clone() as usize];
-                for object in &action.objects {
+                for (object_id, object) in action.objects.iter().enumerate() {
                     for (index, object_index) in object.action.object_table.iter().enumerate() {
-                        if object.info.object_data.key_data[index].key_start_frame <= frame
-                            && object.info.object_data.key_data[index].key_end_frame > frame
-                        {
+                        let key_data = &object.info.object_data.key_data[index];
+                        if extract::key_active(
+                            key_data.key_start_frame,
+                            key_data.key_end_frame,
+                            frame,
+                        ) {
                             let data = &object.action.data[object_index.clone() as usize - 1];
                             match data.name.as_str() {
                                 "CharacterAsset.SteerKey" => {
@@ -917,6 +3706,9 @@ impl Viewer {
                                         }
                                         _ => (),
                                     }
+                                    if !steer_key_applies(&mut ignored, &op_type, &value_type) {
+                                        continue;
+                                    }
                                     let modify_type = &data.fields[4].value;
                                     let mut modify_value = 0f32;
                                     match modify_type {
@@ -925,6 +3717,20 @@ impl Viewer {
                                         }
                                         _ => (),
                                     }
+                                    // Homing keys use the value for something else.
+                                    match (
+                                        &op_type,
+                                        self.steer_overrides[value_type.clone() as usize],
+                                    ) {
+                                        (
+                                            SteerOperationType::SetTarget
+                                            | SteerOperationType::SetHomingValue
+                                            | SteerOperationType::SetHomingTime,
+                                            _,
+                                        ) => (),
+                                        (_, Some(value)) => modify_value = value,
+                                        _ => (),
+                                    }
                                     match value_type {
                                         SteerValueType::VelocityX => {
                                             self.velocity.x = steer_key_to_value(
@@ -976,8 +3782,27 @@ impl Viewer {
                                         }
                                     }
                                     match op_type {
-                                        SteerOperationType::SetNegativeX => {
-                                            if self.velocity.x == 0f32 {
+                                        SteerOperationType::SetInherit => {
+                                            self.inherits_motion = true;
+                                        }
+                                        SteerOperationType::SetInheritXYZ => {
+                                            self.inherits_motion = true;
+                                            match value_type {
+                                                SteerValueType::VelocityX
+                                                | SteerValueType::VelocityY
+                                                | SteerValueType::VelocityZ => {
+                                                    self.velocity = self.prev_velocity.clone();
+                                                }
+                                                SteerValueType::AccelerationX
+                                                | SteerValueType::AccelerationY
+                                                | SteerValueType::AccelerationZ => {
+                                                    self.acceleration =
+                                                        self.prev_acceleration.clone();
+                                                }
+                                            }
+                                        }
+                                        SteerOperationType::SetNegativeX => {
+                                            if self.velocity.x == 0f32 {
                                                 self.acceleration.x = 0f32;
                                             }
                                         }
@@ -991,6 +3816,46 @@ impl Viewer {
                                                 self.acceleration.z = 0f32;
                                             }
                                         }
+                                        SteerOperationType::SetTarget
+                                        | SteerOperationType::SetHomingValue
+                                        | SteerOperationType::SetHomingTime => {
+                                            match op_type {
+                                                SteerOperationType::SetHomingValue => {
+                                                    self.homing_value = modify_value
+                                                }
+                                                SteerOperationType::SetHomingTime => {
+                                                    self.homing_time = modify_value
+                                                }
+                                                _ => (),
+                                            }
+                                            // The target is the opponent standing on the ground.
+                                            match value_type {
+                                                SteerValueType::VelocityX => {
+                                                    self.velocity.x = homing_velocity(
+                                                        self.target_distance
+                                                            - self.position.x
+                                                            - self.root_motion.x,
+                                                        self.homing_time,
+                                                        self.homing_value,
+                                                    )
+                                                }
+                                                SteerValueType::VelocityY => {
+                                                    self.velocity.y = homing_velocity(
+                                                        -self.position.y - self.root_motion.y,
+                                                        self.homing_time,
+                                                        self.homing_value,
+                                                    )
+                                                }
+                                                SteerValueType::VelocityZ => {
+                                                    self.velocity.z = homing_velocity(
+                                                        -self.position.z - self.root_motion.z,
+                                                        self.homing_time,
+                                                        self.homing_value,
+                                                    )
+                                                }
+                                                _ => (),
+                                            }
+                                        }
                                         _ => (),
                                     }
                                 }
@@ -1006,28 +3871,42 @@ impl Viewer {
                                         _ => (),
                                     }
 
+                                    // Only this key runs out, so the rest of the frame
+                                    // still gets simulated.
                                     if frame >= pos_list.len() as i32 {
-                                        return;
+                                        continue;
                                     }
 
+                                    // Spawned objects move on their own; only the first object
+                                    // moves the character.
+                                    let root_motion = if object_id == 0 {
+                                        &mut self.root_motion
+                                    } else {
+                                        if self.object_root_motion.len() <= object_id {
+                                            self.object_root_motion
+                                                .resize_with(object_id + 1, || None);
+                                        }
+                                        self.object_root_motion[object_id]
+                                            .get_or_insert_with(Default::default)
+                                    };
                                     let axis = &data.fields[1].value;
                                     match axis {
                                         RSZValue::UInt8(byte) => match byte {
                                             0 => match pos_list[frame as usize] {
                                                 RSZValue::Float(float) => {
-                                                    self.root_motion.x = float.clone()
+                                                    root_motion.x = float.clone()
                                                 }
                                                 _ => (),
                                             },
                                             1 => match pos_list[frame as usize] {
                                                 RSZValue::Float(float) => {
-                                                    self.root_motion.y = float.clone()
+                                                    root_motion.y = float.clone()
                                                 }
                                                 _ => (),
                                             },
                                             2 => match pos_list[frame as usize] {
                                                 RSZValue::Float(float) => {
-                                                    self.root_motion.z = float.clone()
+                                                    root_motion.z = float.clone()
                                                 }
                                                 _ => (),
                                             },
@@ -1054,8 +3933,11 @@ impl Viewer {
         self.prev_position.y = self.position.y;
         self.prev_position.z = self.position.z;
 
-        if self.position.y < 0f32 {
-            self.position.y = 0f32;
+        if self.landing_frame.is_none() {
+            self.landing_frame = landing_frame(frame, airborne, self.position.y, self.ground_y);
+        }
+        if self.position.y < self.ground_y {
+            self.position.y = self.ground_y;
             self.velocity.x = 0f32;
             self.velocity.y = 0f32;
             self.velocity.z = 0f32;
@@ -1066,6 +3948,22 @@ impl Viewer {
         if self.root_motion.y < 0f32 {
             self.root_motion.y = 0f32;
         }
+        // The pushbox edge stops at the wall, whether the sim or root motion moved it there.
+        self.wall_pinned = false;
+        if self.use_wall {
+            let half_width = self.dummy_pushbox.width;
+            let x = self.position.x + self.root_motion.x;
+            if self.wall_x >= 0f32 && x > self.wall_x - half_width {
+                self.position.x = self.wall_x - half_width - self.root_motion.x;
+                self.wall_pinned = true;
+            } else if self.wall_x < 0f32 && x < self.wall_x + half_width {
+                self.position.x = self.wall_x + half_width - self.root_motion.x;
+                self.wall_pinned = true;
+            }
+        }
+        if self.homing_time > 1f32 {
+            self.homing_time -= 1f32;
+        }
         
         if frame == self.action_info.end_frame
         {
@@ -1078,433 +3976,579 @@ impl Viewer {
         }
     }
 
-    fn get_triggers(&mut self, group: i32, condition_flag: u32) {
-        match &self.asset {
-            Some(fchar) => {
-                let mut data_index: usize = 0;
-                for (n, data_id) in fchar.data_id_table.iter().enumerate() {
-                    match data_id {
-                        DataId::TriggerGroup => data_index = n,
-                        _ => (),
-                    }
-                }
-                let mut index: usize = 0;
-                for (n, value) in fchar.data_list_table[data_index]
-                    .data_ids
-                    .iter()
-                    .enumerate()
-                {
-                    if value.clone() == group as u32 {
-                        index = n;
-                    }
-                }
-                if index == 0 {
-                    return;
-                }
-                let mut triggers: Vec<u64> = vec![];
-                let trigger_group = &fchar.data_list_table[data_index].data_rsz.data[index];
-                match &trigger_group.fields[1].value {
-                    RSZValue::List(list) => {
-                        for select_trigger in list {
-                            match select_trigger {
-                                RSZValue::UInt64(ulong) => {
-                                    triggers.push(ulong.clone());
-                                }
-                                _ => (),
+    /// One row per frame of the selected action. Clicking a frame number jumps to it.
+    fn render_frame_table(&mut self, ui: &mut egui::Ui) {
+        let mut clicked_frame: Option<usize> = None;
+        egui::ScrollArea::vertical()
+            .id_source("Frame data")
+            .max_height(300.0)
+            .show(ui, |ui| {
+                egui::Grid::new("Frame data grid")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("Frame");
+                        ui.label("Attack");
+                        ui.label("Hurt");
+                        ui.label("Push");
+                        ui.label("Velocity X");
+                        ui.label("Velocity Y");
+                        ui.label("Cancel");
+                        ui.end_row();
+                        for (index, frame_boxes) in self.frame_cache.iter().enumerate() {
+                            let frame = index + 1;
+                            if ui
+                                .selectable_label(frame == self.current_frame, format!("{}", frame))
+                                .clicked()
+                            {
+                                clicked_frame = Some(frame);
                             }
-                        }
-                    }
-                    _ => (),
-                }
-                for (trigger_index, select_trigger) in triggers.iter().enumerate() {
-                    let bits: BitVec = BitVec::from_element(select_trigger.clone() as usize);
-                    for (bit_index, bit) in bits.iter().enumerate() {
-                        if bit == false {
-                            continue;
-                        }
-                        let mut data_index: usize = 0;
-                        for (n, data_id) in fchar.data_id_table.iter().enumerate() {
-                            match data_id {
-                                DataId::Trigger => data_index = n,
-                                _ => (),
+                            let attack_boxes: usize = frame_boxes
+                                .attack_collision_keys
+                                .iter()
+                                .filter(|key| !key.is_proximity())
+                                .map(|key| key.boxes.len())
+                                .sum();
+                            ui.label(format!("{}", attack_boxes));
+                            let hurtboxes: usize = frame_boxes
+                                .damage_collision_keys
+                                .iter()
+                                .map(|key| key.hurtboxes().count())
+                                .sum();
+                            ui.label(format!("{}", hurtboxes));
+                            if frame_boxes.push_collision_keys.is_empty() {
+                                ui.label("-");
+                            } else {
+                                ui.label("Yes");
                             }
-                        }
-                        let mut index: usize = 0;
-                        for (n, value) in fchar.data_list_table[data_index]
-                            .data_ids
-                            .iter()
-                            .enumerate()
-                        {
-                            if value.clone() == (bit_index + trigger_index * 64) as u32 {
-                                index = n + 1;
+                            match self.frame_velocities.get(index) {
+                                Some((velocity_x, velocity_y)) => {
+                                    ui.label(format!("{:.3}", velocity_x));
+                                    ui.label(format!("{:.3}", velocity_y));
+                                }
+                                None => {
+                                    ui.label("-");
+                                    ui.label("-");
+                                }
                             }
-                        }
-                        let mut stored_trigger: Trigger = Default::default();
-                        stored_trigger.condition_flag = condition_flag;
-                        let trigger =
-                            &fchar.data_list_table[data_index].data_rsz.data[index * 17 - 1];
-                        match &trigger.fields[5].value {
-                            RSZValue::Int32(action) => {
-                                stored_trigger.action = action.clone();
+                            if frame_boxes.triggers.is_empty() {
+                                ui.label("-");
+                            } else {
+                                ui.label("Open");
                             }
-                            _ => (),
+                            ui.end_row();
                         }
-                        self.triggers.push(stored_trigger);
-                    }
-                }
-            }
-            None => (),
+                    });
+            });
+        if let Some(frame) = clicked_frame {
+            self.current_frame = frame;
+            self.should_update = true;
         }
     }
 
-    fn get_trigger_keys(&mut self) {
-        self.triggers.clear();
-        let mut groups: Vec<i32> = vec![];
-        let mut condition_flags: Vec<u32> = vec![];
-        match &self.asset {
-            Some(fchar) => {
-                let action = &fchar.action_list[self.selected_index.clone() as usize];
-                for object in &action.objects {
-                    for (index, object_index) in object.action.object_table.iter().enumerate() {
-                        if object.info.object_data.key_data[index].key_start_frame
-                            <= self.current_frame as i32 - 1
-                            && object.info.object_data.key_data[index].key_end_frame
-                                > self.current_frame as i32 - 1
-                        {
-                            let data = &object.action.data[object_index.clone() as usize - 1];
-                            match data.name.as_str() {
-                                "CharacterAsset.TriggerKey" => {
-                                    let group = &data.fields[0].value;
-                                    match group {
-                                        RSZValue::Int32(group) => {
-                                            groups.push(group.clone());
-                                        }
-                                        _ => (),
-                                    }
-                                    let condition_flag = &data.fields[1].value;
-                                    match condition_flag {
-                                        RSZValue::UInt32(condition_flag) => {
-                                            condition_flags.push(condition_flag.clone());
-                                        }
-                                        _ => (),
-                                    }
-                                }
-                                _ => (),
-                            }
-                        }
-                    }
-                }
+    /// Draws one cell per frame: red when an attack box is out, green when only hurtboxes are,
+    /// with a yellow tint where the pushbox changes. Clicking a cell jumps to that frame.
+    fn render_timeline(&mut self, ui: &mut egui::Ui) {
+        if self.timeline.is_empty() {
+            return;
+        }
+        let (response, painter) = ui.allocate_painter(
+            Vec2 {
+                x: ui.available_width(),
+                y: 16.0,
+            },
+            Sense::click(),
+        );
+        let cell_width = response.rect.width() / self.timeline.len() as f32;
+        for (frame, cell) in self.timeline.iter().enumerate() {
+            let rect = Rect::from_min_size(
+                Pos2 {
+                    x: response.rect.min.x + cell_width * frame as f32,
+                    y: response.rect.min.y,
+                },
+                Vec2 {
+                    x: cell_width,
+                    y: response.rect.height(),
+                },
+            );
+            let attack = cell.attack
+                && (self.timeline_kind_filter == 0
+                    || cell.attack_kind_flag & self.timeline_kind_filter > 0);
+            let color = if attack {
+                Color32::from_rgb(200, 40, 40)
+            } else if cell.hurtbox {
+                Color32::from_rgb(40, 160, 40)
+            } else {
+                Color32::from_gray(100)
+            };
+            painter.rect_filled(rect.shrink(0.5), 0.0, color);
+            if cell.invulnerable {
+                painter.rect_filled(
+                    Rect::from_min_size(rect.min, Vec2 { x: rect.width(), y: 4.0 }).shrink(0.5),
+                    0.0,
+                    Color32::from_rgb(120, 200, 255),
+                );
+            }
+            if cell.pushbox_change {
+                painter.rect_filled(
+                    rect.shrink(0.5),
+                    0.0,
+                    Color32::from_rgba_unmultiplied(255, 255, 0, 90),
+                );
+            }
+            if self.cancel_windows.iter().any(|cancel_window| {
+                cancel_window.first_frame <= frame + 1 && frame < cancel_window.last_frame
+            }) {
+                painter.rect_filled(
+                    Rect::from_min_max(
+                        Pos2 {
+                            x: rect.min.x,
+                            y: rect.max.y - 6.0,
+                        },
+                        Pos2 {
+                            x: rect.max.x,
+                            y: rect.max.y - 3.0,
+                        },
+                    )
+                    .shrink2(Vec2 { x: 0.5, y: 0.0 }),
+                    0.0,
+                    Color32::from_rgb(255, 255, 255),
+                );
+            }
+            if frame + 1 == self.current_frame {
+                painter.rect_stroke(
+                    rect,
+                    0.0,
+                    Stroke {
+                        width: 2.0,
+                        color: ui.visuals().strong_text_color(),
+                    },
+                );
             }
-            None => (),
         }
-        for (index, group) in groups.iter().enumerate() {
-            self.get_triggers(group.clone(), condition_flags[index]);
+        for bookmark in self.action_bookmarks() {
+            if bookmark.frame == 0 || bookmark.frame > self.timeline.len() {
+                continue;
+            }
+            let x = response.rect.min.x + cell_width * (bookmark.frame as f32 - 0.5);
+            let top = response.rect.min.y;
+            painter.add(egui::Shape::convex_polygon(
+                vec![
+                    Pos2 { x: x - 4.0, y: top },
+                    Pos2 { x: x + 4.0, y: top },
+                    Pos2 { x, y: top + 6.0 },
+                ],
+                Color32::GOLD,
+                Stroke::NONE,
+            ));
         }
-        self.triggers.sort_unstable();
-        self.triggers.dedup();
-    }
-
-    fn index_to_box(
-        &self,
-        fchar: &CharacterAsset,
-        int: i32,
-        data_type: DataId,
-        boxes: &mut Vec<CollisionBox>,
-    ) {
-        let mut data_index: usize = 0;
-        for (n, data_id) in fchar.data_id_table.iter().enumerate() {
-            if data_id.clone() == data_type {
-                data_index = n;
-            }
-        }
-        let mut index: usize = 0;
-        for (n, value) in fchar.data_list_table[data_index]
-            .data_ids
-            .iter()
-            .enumerate()
-        {
-            if value.clone() == int as u32 {
-                index = n + 1;
+        if response.clicked() {
+            if let Some(pointer_pos) = response.interact_pointer_pos() {
+                let frame = ((pointer_pos.x - response.rect.min.x) / cell_width) as usize + 1;
+                self.current_frame = frame.clamp(1, self.timeline.len());
+                self.should_update = true;
+                ui.ctx().request_repaint();
             }
         }
-        if index == 0 {
-            return;
+    }
+
+    /// Root motion of one of the action's objects. Objects without a PlaceKey of their own
+    /// stay attached to the character.
+    fn object_root_motion(&self, object_id: usize) -> &Vector3f {
+        match self.object_root_motion.get(object_id) {
+            Some(Some(root_motion)) => root_motion,
+            _ => &self.root_motion,
         }
-        let data = &fchar.data_list_table[data_index].data_rsz.data[index * 6 - 1];
-        let x_field = &data.fields[0].value;
-        let mut x = 0f32;
-        match x_field {
-            RSZValue::Int16(short) => x = short.clone() as f32,
-            _ => (),
+    }
+
+    /// Screen X grows to the right, so facing left mirrors every game X around the origin.
+    fn facing_sign(&self) -> f32 {
+        if self.facing_left {
+            -1f32
+        } else {
+            1f32
         }
-        let y_field = &data.fields[1].value;
-        let mut y = 0f32;
-        match y_field {
-            RSZValue::Int16(short) => y = short.clone() as f32,
-            _ => (),
+    }
+
+    /// Converts a game-space point to the screen.
+    fn world_to_screen(&self, x: f32, y: f32) -> Pos2 {
+        Pos2 {
+            x: x * self.facing_sign() * self.zoom + self.offset_x,
+            y: -y * self.zoom + self.offset_y,
         }
-        let width_field = &data.fields[2].value;
-        let mut width = 0f32;
-        match width_field {
-            RSZValue::Int16(short) => width = short.clone() as f32,
-            _ => (),
+    }
+
+    /// Inverse of `world_to_screen`: the game position under a point on the canvas, with X
+    /// pointing forward whichever way the character faces.
+    fn screen_to_world(&self, pos: Pos2) -> (f32, f32) {
+        (
+            (pos.x - self.offset_x) / (self.facing_sign() * self.zoom),
+            -(pos.y - self.offset_y) / self.zoom,
+        )
+    }
+
+    /// Where a box sits in game space, before mirroring. Y points up.
+    fn world_box(
+        &self,
+        collision_box: &CollisionBox,
+        follows_position: bool,
+        object_id: usize,
+    ) -> Rect {
+        let mut x = collision_box.x;
+        let mut y = collision_box.y;
+        if follows_position {
+            let root_motion = self.object_root_motion(object_id);
+            x += self.position.x + root_motion.x;
+            y += self.position.y + root_motion.y;
         }
-        let height_field = &data.fields[3].value;
-        let mut height = 0f32;
-        match height_field {
-            RSZValue::Int16(short) => height = short.clone() as f32,
-            _ => (),
+        Rect::from_center_size(
+            Pos2 { x, y },
+            Vec2 {
+                x: collision_box.width * 2.0,
+                y: collision_box.height * 2.0,
+            },
+        )
+    }
+
+    /// Converts a box to screen space. Boxes that don't follow the character, like proximity
+    /// guard boxes, are drawn relative to the starting origin instead.
+    fn box_rect(
+        &self,
+        collision_box: &CollisionBox,
+        follows_position: bool,
+        object_id: usize,
+    ) -> Rect {
+        let world_box = self.world_box(collision_box, follows_position, object_id);
+        let center = self.world_to_screen(world_box.center().x, world_box.center().y);
+        let half_width = collision_box.width * self.zoom;
+        let half_height = collision_box.height * self.zoom;
+        Rect {
+            min: Pos2 {
+                x: center.x - half_width + 0.5,
+                y: center.y - half_height + 0.5,
+            },
+            max: Pos2 {
+                x: center.x + half_width - 0.5,
+                y: center.y + half_height - 0.5,
+            },
         }
-        let collision_box: CollisionBox = CollisionBox {
-            x,
-            y,
-            width,
-            height,
+    }
+
+    /// Where the dummy opponent's pushbox is drawn. It stands at the target, facing the player.
+    fn dummy_rect(&self) -> Rect {
+        let pushbox = CollisionBox {
+            x: self.target_distance - self.dummy_pushbox.x,
+            ..self.dummy_pushbox.clone()
         };
-        boxes.push(collision_box);
+        self.box_rect(&pushbox, false, 0)
     }
 
-    fn get_boxes(&mut self) {
-        self.push_collision_keys.clear();
-        self.damage_collision_keys.clear();
-        self.attack_collision_keys.clear();
-        match &self.asset {
-            Some(fchar) => {
-                let action = &fchar.action_list[self.selected_index.clone() as usize];
-                for object in &action.objects {
-                    for (index, object_index) in object.action.object_table.iter().enumerate() {
-                        if object.info.object_data.key_data[index].key_start_frame
-                            <= self.current_frame as i32 - 1
-                            && object.info.object_data.key_data[index].key_end_frame
-                                > self.current_frame as i32 - 1
-                        {
-                            let data = &object.action.data[object_index.clone() as usize - 1];
-                            match data.name.as_str() {
-                                "CharacterAsset.PushCollisionKey" => {
-                                    let mut boxes: Vec<CollisionBox> = vec![];
-                                    let mut pushbox: CollisionBox = Default::default();
+    /// Draws a grid every `GRID_SPACING` units with the axes through the origin, labelling each
+    /// line with its value along the edge of the axis so reach can be read off directly.
+    fn render_grid(&self, painter: &egui::Painter, clip_rect: Rect, dark_mode: bool) {
+        let (grid_color, axis_color, label_color) = if dark_mode {
+            (Color32::from_gray(45), Color32::from_gray(120), Color32::from_gray(140))
+        } else {
+            (Color32::from_gray(225), Color32::from_gray(140), Color32::from_gray(110))
+        };
+        // Keep the labels on screen even when the axis itself has been panned out of view.
+        let label_x = (self.offset_x + 2.0).clamp(clip_rect.min.x, clip_rect.max.x - 30.0);
+        let label_y = (self.offset_y + 2.0).clamp(clip_rect.min.y, clip_rect.max.y - 12.0);
 
-                                    let mut condition = 0u8;
-                                    match &data.fields[0].value {
-                                        RSZValue::UInt8(ubyte) => condition = ubyte.clone(),
-                                        _ => (),
-                                    }
-                                    let mut attribute = 0u16;
-                                    match &data.fields[1].value {
-                                        RSZValue::UInt16(ushort) => attribute = ushort.clone(),
-                                        _ => (),
-                                    }
-                                    match &data.fields[2].value {
-                                        RSZValue::Int32(int) => {
-                                            self.index_to_box(
-                                                &fchar,
-                                                int.clone(),
-                                                DataId::ThrowHurtBox,
-                                                &mut boxes,
-                                            );
-                                        }
-                                        _ => (),
-                                    }
+        let spacing = GRID_SPACING * self.zoom;
+        let first_column = ((clip_rect.min.x - self.offset_x) / spacing).floor() as i32;
+        let last_column = ((clip_rect.max.x - self.offset_x) / spacing).ceil() as i32;
+        for column in first_column..=last_column {
+            let x = self.offset_x + column as f32 * spacing;
+            let color = if column == 0 { axis_color } else { grid_color };
+            painter.line_segment(
+                [
+                    Pos2 { x, y: clip_rect.min.y },
+                    Pos2 { x, y: clip_rect.max.y },
+                ],
+                Stroke { width: 1.0, color },
+            );
+            let column_value = if self.facing_left { -column } else { column };
+            painter.text(
+                Pos2 { x: x + 2.0, y: label_y },
+                Align2::LEFT_TOP,
+                format!("{}", column_value as f32 * GRID_SPACING),
+                FontId::monospace(10.0),
+                label_color,
+            );
+        }
 
-                                    if boxes.len() > 0 {
-                                        pushbox = boxes[0].clone();
-                                    }
+        // Game Y points up, so rows above the origin are positive.
+        let first_row = ((self.offset_y - clip_rect.max.y) / spacing).floor() as i32;
+        let last_row = ((self.offset_y - clip_rect.min.y) / spacing).ceil() as i32;
+        for row in first_row..=last_row {
+            let y = self.offset_y - row as f32 * spacing;
+            let color = if row == 0 { axis_color } else { grid_color };
+            painter.line_segment(
+                [
+                    Pos2 { x: clip_rect.min.x, y },
+                    Pos2 { x: clip_rect.max.x, y },
+                ],
+                Stroke { width: 1.0, color },
+            );
+            if row != 0 {
+                painter.text(
+                    Pos2 { x: label_x, y: y + 2.0 },
+                    Align2::LEFT_TOP,
+                    format!("{}", row as f32 * GRID_SPACING),
+                    FontId::monospace(10.0),
+                    label_color,
+                );
+            }
+        }
+    }
 
-                                    let push_collision = PushCollisionKey {
-                                        condition,
-                                        attribute,
-                                        pushbox,
-                                    };
-                                    self.push_collision_keys.push(push_collision)
-                                }
-                                "CharacterAsset.DamageCollisionKey" => {
-                                    let mut boxes: Vec<CollisionBox> = vec![];
+    /// Outlines another frame's boxes at low alpha. They are placed with the current position,
+    /// so they show how the boxes change relative to the character.
+    fn render_diff_boxes(&self, painter: &egui::Painter) {
+        let action_diff = match &self.action_diff {
+            Some(action_diff) => action_diff,
+            None => return,
+        };
+        for box_change in action_diff.frame_boxes(self.current_frame - 1) {
+            let rect = |collision_box: &CollisionBox| {
+                self.box_rect(
+                    collision_box,
+                    box_change.follows_position(),
+                    box_change.object_id,
+                )
+            };
+            let added_stroke = Stroke {
+                width: 2.0,
+                color: DIFF_ADDED_COLOR,
+            };
+            let removed_stroke = Stroke {
+                width: 2.0,
+                color: DIFF_REMOVED_COLOR,
+            };
+            match &box_change.change {
+                BoxChangeKind::Added => {
+                    painter.rect_stroke(rect(&box_change.collision_box), 0.0, added_stroke);
+                }
+                BoxChangeKind::Removed => {
+                    dashed_rect(painter, rect(&box_change.collision_box), removed_stroke);
+                }
+                BoxChangeKind::Changed(previous_box) => {
+                    dashed_rect(painter, rect(previous_box), removed_stroke);
+                    painter.rect_stroke(rect(&box_change.collision_box), 0.0, added_stroke);
+                }
+            }
+        }
+    }
 
-                                    let mut head_list: &Vec<RSZValue> = &vec![];
-                                    match &data.fields[9].value {
-                                        RSZValue::List(list) => head_list = list,
-                                        _ => (),
-                                    }
-                                    for head_index in head_list {
-                                        match head_index {
-                                            RSZValue::Int32(int) => {
-                                                self.index_to_box(
-                                                    &fchar,
-                                                    int.clone(),
-                                                    DataId::HurtBox,
-                                                    &mut boxes,
-                                                );
-                                            }
-                                            _ => (),
-                                        }
-                                    }
-                                    let mut body_list: &Vec<RSZValue> = &vec![];
-                                    match &data.fields[10].value {
-                                        RSZValue::List(list) => body_list = list,
-                                        _ => (),
-                                    }
-                                    for body_index in body_list {
-                                        match body_index {
-                                            RSZValue::Int32(int) => {
-                                                self.index_to_box(
-                                                    &fchar,
-                                                    int.clone(),
-                                                    DataId::HurtBox,
-                                                    &mut boxes,
-                                                );
-                                            }
-                                            _ => (),
-                                        }
-                                    }
-                                    let mut leg_list: &Vec<RSZValue> = &vec![];
-                                    match &data.fields[11].value {
-                                        RSZValue::List(list) => leg_list = list,
-                                        _ => (),
-                                    }
-                                    for leg_index in leg_list {
-                                        match leg_index {
-                                            RSZValue::Int32(int) => {
-                                                self.index_to_box(
-                                                    &fchar,
-                                                    int.clone(),
-                                                    DataId::HurtBox,
-                                                    &mut boxes,
-                                                );
-                                            }
-                                            _ => (),
-                                        }
-                                    }
-                                    let mut throw_list: &Vec<RSZValue> = &vec![];
-                                    match &data.fields[12].value {
-                                        RSZValue::List(list) => throw_list = list,
-                                        _ => (),
-                                    }
+    fn render_ghost_boxes(&self, painter: &egui::Painter, frame_boxes: &FrameBoxes) {
+        let ghost_stroke = |color: Color32| Stroke {
+            width: 1.0,
+            color: color.gamma_multiply(0.3),
+        };
+        for push_collision_key in &frame_boxes.push_collision_keys {
+            painter.rect_stroke(
+                self.box_rect(
+                    &push_collision_key.pushbox,
+                    true,
+                    push_collision_key.object_id,
+                ),
+                0.0,
+                ghost_stroke(self.draw_colors.pushbox.stroke),
+            );
+        }
+        for damage_collision_key in &frame_boxes.damage_collision_keys {
+            for hurtbox in damage_collision_key.hurtboxes() {
+                painter.rect_stroke(
+                    self.box_rect(hurtbox, true, damage_collision_key.object_id),
+                    0.0,
+                    ghost_stroke(self.draw_colors.hurtbox.stroke),
+                );
+            }
+            for throw_box in &damage_collision_key.throw_boxes {
+                painter.rect_stroke(
+                    self.box_rect(throw_box, true, damage_collision_key.object_id),
+                    0.0,
+                    ghost_stroke(self.draw_colors.throw_hurtbox.stroke),
+                );
+            }
+        }
+        for attack_collision_key in &frame_boxes.attack_collision_keys {
+            for hitbox in &attack_collision_key.boxes {
+                if attack_collision_key.is_proximity() {
+                    dashed_rect(
+                        painter,
+                        self.box_rect(hitbox, false, attack_collision_key.object_id),
+                        ghost_stroke(self.draw_colors.proximity.stroke),
+                    );
+                } else {
+                    let hitbox = offset_box(hitbox, attack_collision_key.hit_offset);
+                    painter.rect_stroke(
+                        self.box_rect(&hitbox, true, attack_collision_key.object_id),
+                        0.0,
+                        ghost_stroke(
+                            self.draw_colors
+                                .guard(attack_collision_key.guard_requirement())
+                                .stroke,
+                        ),
+                    );
+                }
+            }
+        }
+    }
 
-                                    let mut condition = 0u8;
-                                    match &data.fields[0].value {
-                                        RSZValue::UInt8(ubyte) => condition = ubyte.clone(),
-                                        _ => (),
-                                    }
-                                    let mut collision_type = 0u8;
-                                    match &data.fields[1].value {
-                                        RSZValue::UInt8(ubyte) => collision_type = ubyte.clone(),
-                                        _ => (),
-                                    }
-                                    let mut immune = 0u8;
-                                    match &data.fields[2].value {
-                                        RSZValue::UInt8(ubyte) => immune = ubyte.clone(),
-                                        _ => (),
-                                    }
-                                    let mut extend = 0u8;
-                                    match &data.fields[3].value {
-                                        RSZValue::UInt8(ubyte) => extend = ubyte.clone(),
-                                        _ => (),
-                                    }
-                                    let mut level = 0u8;
-                                    match &data.fields[4].value {
-                                        RSZValue::UInt8(ubyte) => level = ubyte.clone(),
-                                        _ => (),
-                                    }
-                                    let mut type_flag = 0u32;
-                                    match &data.fields[5].value {
-                                        RSZValue::UInt32(uint) => type_flag = uint.clone(),
-                                        _ => (),
-                                    }
+    /// Fills in the hitboxes of every frame but the current one at low alpha, each moved to where
+    /// the character was on its frame. Overlapping frames stack up, so the areas a move covers
+    /// for longer come out stronger.
+    fn render_all_hitboxes(&self, painter: &egui::Painter) {
+        for (frame, frame_boxes) in self.frame_cache.iter().enumerate() {
+            if frame + 1 == self.current_frame {
+                continue;
+            }
+            let motion_state = match self.frame_motion.get(frame) {
+                Some(motion_state) => motion_state,
+                None => continue,
+            };
+            for attack_collision_key in &frame_boxes.attack_collision_keys {
+                if attack_collision_key.is_proximity() {
+                    continue;
+                }
+                let object_id = attack_collision_key.object_id;
+                let (x, y) = motion_state.object_position(object_id);
+                let root_motion = self.object_root_motion(object_id);
+                let shift = Vec2 {
+                    x: (x - self.position.x - root_motion.x) * self.facing_sign() * self.zoom,
+                    y: -(y - self.position.y - root_motion.y) * self.zoom,
+                };
+                let fill = self
+                    .draw_colors
+                    .guard(attack_collision_key.guard_requirement())
+                    .fill
+                    .gamma_multiply(0.4);
+                for hitbox in &attack_collision_key.boxes {
+                    let hitbox = offset_box(hitbox, attack_collision_key.hit_offset);
+                    painter.rect_filled(
+                        self.box_rect(&hitbox, true, object_id).translate(shift),
+                        0.0,
+                        fill,
+                    );
+                }
+            }
+        }
+    }
 
-                                    let damage_collision = DamageCollisionKey {
-                                        condition,
-                                        collision_type,
-                                        immune,
-                                        extend,
-                                        level,
-                                        type_flag,
-                                        boxes,
-                                    };
-                                    self.damage_collision_keys.push(damage_collision)
-                                }
-                                "CharacterAsset.AttackCollisionKey" => {
-                                    let mut boxes: Vec<CollisionBox> = vec![];
+    /// Draws the character's path over the whole action with a dot per frame, the current
+    /// frame's dot larger.
+    fn render_trajectory(&self, painter: &egui::Painter) {
+        let color = Color32::from_rgb(200, 100, 255);
+        // With steer overrides set, the path the file gives is dashed under the changed one.
+        if self.original_positions.len() > 1 {
+            let original_points: Vec<Pos2> = self
+                .original_positions
+                .iter()
+                .map(|(x, y)| self.world_to_screen(*x, *y))
+                .collect();
+            painter.extend(egui::Shape::dashed_line(
+                &original_points,
+                Stroke {
+                    width: 1.0,
+                    color: Color32::GRAY,
+                },
+                4.0,
+                3.0,
+            ));
+        }
+        let points: Vec<Pos2> = self
+            .frame_positions
+            .iter()
+            .map(|(x, y)| self.world_to_screen(*x, *y))
+            .collect();
+        painter.add(egui::Shape::line(
+            points.clone(),
+            Stroke {
+                width: 1.0,
+                color: color.gamma_multiply(0.6),
+            },
+        ));
+        for (index, point) in points.into_iter().enumerate() {
+            if index + 1 == self.current_frame {
+                painter.circle(point, 4.0, color, Stroke::new(1.0, Color32::WHITE));
+            } else {
+                painter.circle_filled(point, 2.0, color);
+            }
+        }
+    }
 
-                                    let mut condition = 0u8;
-                                    match &data.fields[0].value {
-                                        RSZValue::UInt8(ubyte) => condition = ubyte.clone(),
-                                        _ => (),
-                                    }
-                                    let mut collision_type = 0u8;
-                                    match &data.fields[1].value {
-                                        RSZValue::UInt8(ubyte) => collision_type = ubyte.clone(),
-                                        _ => (),
-                                    }
-                                    let mut hit_id = 0i8;
-                                    match &data.fields[2].value {
-                                        RSZValue::Int8(byte) => hit_id = byte.clone(),
-                                        _ => (),
-                                    }
-                                    let mut guard_bit = 0u8;
-                                    match &data.fields[3].value {
-                                        RSZValue::UInt8(ubyte) => guard_bit = ubyte.clone(),
-                                        _ => (),
-                                    }
-                                    let mut kind_flag = 0u32;
-                                    match &data.fields[4].value {
-                                        RSZValue::UInt32(uint) => kind_flag = uint.clone(),
-                                        _ => (),
-                                    }
-                                    let mut hit_offset = [0; 2];
-                                    match &data.fields[4].value {
-                                        RSZValue::Int2(int2) => {
-                                            hit_offset[0] = int2.x.clone();
-                                            hit_offset[1] = int2.y.clone();
-                                        }
-                                        _ => (),
-                                    }
+    /// Adds the current position to the trail when the frame just advanced by one, and starts
+    /// the trail over on any other jump. Updating the same frame again, e.g. after moving the
+    /// dummy, replaces its position.
+    fn update_trail(&mut self) {
+        let (trail_index, trail_frame) = self.trail_frame;
+        if trail_index != self.selected_index {
+            self.trail.clear();
+        } else if self.current_frame == trail_frame {
+            self.trail.pop();
+        } else if self.current_frame != trail_frame + 1 {
+            self.trail.clear();
+        }
+        self.trail.push((
+            self.position.x + self.root_motion.x,
+            self.position.y + self.root_motion.y,
+        ));
+        if self.trail.len() > self.trail_length {
+            let excess = self.trail.len() - self.trail_length;
+            self.trail.drain(..excess);
+        }
+        self.trail_frame = (self.selected_index, self.current_frame);
+    }
 
-                                    let mut box_list: &Vec<RSZValue> = &vec![];
-                                    match &data.fields[11].value {
-                                        RSZValue::List(list) => box_list = list,
-                                        _ => (),
-                                    }
-                                    for index in box_list {
-                                        match index {
-                                            RSZValue::Int32(int) => {
-                                                if collision_type == 3 {
-                                                    self.index_to_box(
-                                                        &fchar,
-                                                        int.clone(),
-                                                        DataId::ProximityBox,
-                                                        &mut boxes,
-                                                    );
-                                                } else {
-                                                    self.index_to_box(
-                                                        &fchar,
-                                                        int.clone(),
-                                                        DataId::StrikeBox,
-                                                        &mut boxes,
-                                                    );
-                                                }
-                                            }
-                                            _ => (),
-                                        }
-                                    }
+    fn render_trail(&self, painter: &egui::Painter) {
+        let count = self.trail.len().min(self.trail_length);
+        let trail = &self.trail[self.trail.len() - count..];
+        for (index, (x, y)) in trail.iter().enumerate() {
+            let fade = (index + 1) as f32 / (count + 1) as f32;
+            painter.circle_filled(
+                self.world_to_screen(*x, *y),
+                3.0,
+                Color32::GRAY.gamma_multiply(fade),
+            );
+        }
+    }
 
-                                    let attack_collision = AttackCollisionKey {
-                                        condition,
-                                        collision_type,
-                                        hit_id,
-                                        guard_bit,
-                                        kind_flag,
-                                        hit_offset,
-                                        boxes,
-                                    };
-                                    self.attack_collision_keys.push(attack_collision)
-                                }
-                                _ => (),
-                            }
-                        }
-                    }
-                }
-            }
-            None => (),
+    /// Lists the hitbox and hurtbox colors in the top right corner of the canvas.
+    fn render_legend(&self, painter: &egui::Painter, clip_rect: Rect) {
+        let text_color = painter.ctx().style().visuals.text_color();
+        let mut pos = Pos2 {
+            x: clip_rect.max.x - 100.0,
+            y: clip_rect.min.y + 8.0,
+        };
+        let mut entries: Vec<(String, Color32)> = vec![];
+        for guard_requirement in GUARD_REQUIREMENTS {
+            entries.push((
+                guard_requirement.to_string(),
+                self.draw_colors.guard(guard_requirement).stroke,
+            ));
+        }
+        entries.push(("Throw".to_string(), self.draw_colors.throw.stroke));
+        for region in HURTBOX_REGIONS {
+            entries.push((
+                format!("{} hurtbox", region),
+                self.draw_colors.hurtbox_region(region).stroke,
+            ));
+        }
+        for (label, color) in entries {
+            painter.rect_filled(
+                Rect::from_min_size(pos, Vec2 { x: 10.0, y: 10.0 }),
+                0.0,
+                color,
+            );
+            painter.text(
+                pos + Vec2 { x: 16.0, y: 5.0 },
+                Align2::LEFT_CENTER,
+                label,
+                FontId::proportional(12.0),
+                text_color,
+            );
+            pos.y += 16.0;
         }
     }
 
@@ -1516,171 +4560,497 @@ impl Viewer {
             },
             Sense::click_and_drag(),
         );
-        if let Some(pointer_pos) = response.interact_pointer_pos() {
+        if self.measuring {
+            if response.drag_started_by(egui::PointerButton::Primary) {
+                self.measure_start = response.interact_pointer_pos();
+            }
+            if response.dragged_by(egui::PointerButton::Primary) {
+                self.measure_end = response.interact_pointer_pos();
+            }
+        } else if let Some(pointer_pos) = response.interact_pointer_pos() {
+            if response.drag_started_by(egui::PointerButton::Primary) {
+                self.dragging_dummy = self.show_dummy && self.dummy_rect().contains(pointer_pos);
+            }
             if self.last_cursor_pos != Default::default() {
                 let pointer_delta = pointer_pos - self.last_cursor_pos;
-                self.offset_x += pointer_delta.x;
-                self.offset_y += pointer_delta.y;
+                if self.dragging_dummy {
+                    self.target_distance = (self.target_distance
+                        + pointer_delta.x * self.facing_sign() / self.zoom)
+                        .clamp(0.0, 1000.0);
+                    self.invalidate_motion();
+                } else {
+                    self.offset_x += pointer_delta.x;
+                    self.offset_y += pointer_delta.y;
+                }
                 response.mark_changed();
             }
             self.last_cursor_pos = pointer_pos;
         } else {
             self.last_cursor_pos = Default::default();
+            self.dragging_dummy = false;
         }
-        if response.clicked_by(egui::PointerButton::Secondary) {
-            self.offset_x = 90.0;
-            self.offset_y = 300.0;
+        if self.fit_pending {
+            self.fit_view(response.rect);
+            self.fit_pending = false;
         }
-        for push_collision_key in &self.push_collision_keys {
-            painter.rect(
-                Rect {
-                    min: Pos2 {
-                        x: push_collision_key.pushbox.x.clone()
-                            - push_collision_key.pushbox.width.clone()
-                            + self.offset_x.clone()
-                            + 0.5
-                            + self.position.x
-                            + self.root_motion.x,
-                        y: -push_collision_key.pushbox.y.clone()
-                            - push_collision_key.pushbox.height.clone()
-                            + self.offset_y.clone()
-                            + 0.5
-                            - self.position.y
-                            - self.root_motion.y,
+        if self.follow_character {
+            self.canvas_rect = response.rect;
+            self.center_on_character();
+        }
+        let dark_mode = ui.ctx().style().visuals.dark_mode;
+        self.draw_colors = self.box_colors.for_background(dark_mode);
+        self.render_grid(&painter, response.rect, dark_mode);
+        let ground_y = self.offset_y - self.ground_y * self.zoom;
+        painter.line_segment(
+            [
+                Pos2 {
+                    x: response.rect.min.x,
+                    y: ground_y,
+                },
+                Pos2 {
+                    x: response.rect.max.x,
+                    y: ground_y,
+                },
+            ],
+            Stroke {
+                width: 2.0,
+                color: Color32::from_rgb(150, 110, 60),
+            },
+        );
+        if self.use_wall {
+            let wall_x = self.world_to_screen(self.wall_x, 0.0).x;
+            painter.line_segment(
+                [
+                    Pos2 {
+                        x: wall_x,
+                        y: response.rect.min.y,
                     },
-                    max: Pos2 {
-                        x: push_collision_key.pushbox.x.clone()
-                            + push_collision_key.pushbox.width.clone()
-                            + self.offset_x.clone()
-                            - 0.5
-                            + self.position.x
-                            + self.root_motion.x,
-                        y: -push_collision_key.pushbox.y.clone()
-                            + push_collision_key.pushbox.height.clone()
-                            + self.offset_y.clone()
-                            - 0.5
-                            - self.position.y
-                            - self.root_motion.y,
+                    Pos2 {
+                        x: wall_x,
+                        y: ground_y,
                     },
+                ],
+                Stroke {
+                    width: 3.0,
+                    color: Color32::from_rgb(150, 110, 60),
                 },
+            );
+        }
+        if self.show_trajectory || !self.original_positions.is_empty() {
+            self.render_trajectory(&painter);
+        }
+        if self.show_trail {
+            self.render_trail(&painter);
+        }
+        let dummy_rect = self.dummy_rect();
+        if self.show_dummy {
+            painter.rect(
+                dummy_rect,
                 0.0,
-                egui::Rgba::from_rgba_unmultiplied(0.8, 0.8, 0.0, 0.25),
+                Color32::from_rgba_unmultiplied(255, 140, 0, 40),
                 Stroke {
                     width: 1.0,
-                    color: Color32::YELLOW,
+                    color: Color32::from_rgb(255, 140, 0),
+                },
+            );
+        }
+        let mut dummy_hit = false;
+        let mut dummy_thrown = false;
+        // The closest any throw box on this frame gets to the dummy.
+        let mut throw_gap: Option<f32> = None;
+        let hover_pos = response.hover_pos();
+        let mut hovered_boxes: Vec<String> = vec![];
+        let mut box_rects: Vec<Rect> = vec![];
+        let mut box_labels: Vec<(Rect, String)> = vec![];
+        let mut own_hurtbox_rects: Vec<Rect> = vec![];
+        if self.show_ghost_frame && self.current_frame > 1 {
+            match self.frame_cache.get(self.current_frame - 2) {
+                Some(ghost_boxes) => self.render_ghost_boxes(&painter, ghost_boxes),
+                None => (),
+            }
+        }
+        if self.show_all_hitboxes {
+            self.render_all_hitboxes(&painter);
+        }
+        for push_collision_key in &self.push_collision_keys {
+            let rect = self.box_rect(
+                &push_collision_key.pushbox,
+                true,
+                push_collision_key.object_id,
+            );
+            painter.rect(
+                rect,
+                self.box_rounding,
+                self.draw_colors.pushbox.fill,
+                Stroke {
+                    width: self.box_stroke_width,
+                    color: self.draw_colors.pushbox.stroke,
                 },
             );
+            box_rects.push(rect);
+            box_labels.push((rect, box_values(&push_collision_key.pushbox)));
+            if hover_pos.is_some_and(|pos| rect.contains(pos)) {
+                hovered_boxes.push(format!(
+                    "Pushbox{}: condition {}, attribute {:#06x}{}",
+                    object_label(push_collision_key.object_id),
+                    push_collision_key.condition,
+                    push_collision_key.attribute,
+                    unverified_names(push_collision_key.attribute.into(), &PUSH_ATTRIBUTE_FLAGS)
+                ));
+            }
         }
         for damage_collision_key in &self.damage_collision_keys {
-            for hurtbox in &damage_collision_key.boxes {
-                painter.rect(
-                    Rect {
-                        min: Pos2 {
-                            x: hurtbox.x.clone() - hurtbox.width.clone()
-                                + self.offset_x.clone()
-                                + 0.5
-                                + self.position.x
-                                + self.root_motion.x,
-                            y: -hurtbox.y.clone() - hurtbox.height.clone()
-                                + self.offset_y.clone()
-                                + 0.5
-                                - self.position.y
-                                - self.root_motion.y,
-                        },
-                        max: Pos2 {
-                            x: hurtbox.x.clone() + hurtbox.width.clone() + self.offset_x.clone()
-                                - 0.5
-                                + self.position.x
-                                + self.root_motion.x,
-                            y: -hurtbox.y.clone() + hurtbox.height.clone() + self.offset_y.clone()
-                                - 0.5
-                                - self.position.y
-                                - self.root_motion.y,
+            for (region, hurtboxes) in damage_collision_key.regions() {
+                let style = self.draw_colors.hurtbox_region(region);
+                for hurtbox in hurtboxes {
+                    let rect = self.box_rect(hurtbox, true, damage_collision_key.object_id);
+                    if damage_collision_key.object_id == 0 {
+                        own_hurtbox_rects.push(rect);
+                    }
+                    painter.rect(
+                        rect,
+                        self.box_rounding,
+                        style.fill,
+                        Stroke {
+                            width: self.box_stroke_width,
+                            color: style.stroke,
                         },
-                    },
-                    0.0,
-                    egui::Rgba::from_rgba_unmultiplied(0.0, 0.8, 0.0, 0.25),
+                    );
+                    box_rects.push(rect);
+                    box_labels.push((rect, box_values(hurtbox)));
+                    if hover_pos.is_some_and(|pos| rect.contains(pos)) {
+                        hovered_boxes.push(format!(
+                            "{} hurtbox{}: level {}, immune {:#04x}{}, type flag {:#010x}{}",
+                            region,
+                            object_label(damage_collision_key.object_id),
+                            damage_collision_key.level,
+                            damage_collision_key.immune,
+                            unverified_names(damage_collision_key.immune.into(), &IMMUNE_FLAGS),
+                            damage_collision_key.type_flag,
+                            unverified_names(damage_collision_key.type_flag, &DAMAGE_TYPE_FLAGS)
+                        ));
+                    }
+                }
+            }
+            for throw_box in &damage_collision_key.throw_boxes {
+                let rect = self.box_rect(throw_box, true, damage_collision_key.object_id);
+                painter.rect(
+                    rect,
+                    self.box_rounding,
+                    self.draw_colors.throw_hurtbox.fill,
                     Stroke {
-                        width: 1.0,
-                        color: Color32::GREEN,
+                        width: self.box_stroke_width,
+                        color: self.draw_colors.throw_hurtbox.stroke,
                     },
                 );
+                box_rects.push(rect);
+                box_labels.push((rect, box_values(throw_box)));
+                if hover_pos.is_some_and(|pos| rect.contains(pos)) {
+                    hovered_boxes.push(format!(
+                        "Throw hurtbox{}: level {}, immune {:#04x}{}, type flag {:#010x}{}",
+                        object_label(damage_collision_key.object_id),
+                        damage_collision_key.level,
+                        damage_collision_key.immune,
+                        unverified_names(damage_collision_key.immune.into(), &IMMUNE_FLAGS),
+                        damage_collision_key.type_flag,
+                        unverified_names(damage_collision_key.type_flag, &DAMAGE_TYPE_FLAGS)
+                    ));
+                }
             }
         }
         for attack_collision_key in &self.attack_collision_keys {
             for hitbox in &attack_collision_key.boxes {
-                if attack_collision_key.collision_type == 3 {
-                    painter.rect(
-                        Rect {
-                            min: Pos2 {
-                                x: hitbox.x.clone() - hitbox.width.clone()
-                                    + self.offset_x.clone()
-                                    + 0.5,
-                                y: -hitbox.y.clone() - hitbox.height.clone()
-                                    + self.offset_y.clone()
-                                    + 0.5,
-                            },
-                            max: Pos2 {
-                                x: hitbox.x.clone() + hitbox.width.clone() + self.offset_x.clone()
-                                    - 0.5,
-                                y: -hitbox.y.clone()
-                                    + hitbox.height.clone()
-                                    + self.offset_y.clone()
-                                    - 0.5,
-                            },
-                        },
-                        0.0,
-                        egui::Rgba::from_rgba_unmultiplied(0.5, 0.5, 0.5, 0.25),
+                if attack_collision_key.is_proximity() {
+                    let rect = self.box_rect(hitbox, false, attack_collision_key.object_id);
+                    painter.rect_filled(rect, 0.0, self.draw_colors.proximity.fill);
+                    dashed_rect(
+                        &painter,
+                        rect,
                         Stroke {
-                            width: 1.0,
-                            color: Color32::GRAY,
+                            width: self.box_stroke_width,
+                            color: self.draw_colors.proximity.stroke,
                         },
                     );
+                    box_rects.push(rect);
+                    box_labels.push((
+                        rect,
+                        format!(
+                            "{} #{} proximity",
+                            box_values(hitbox),
+                            attack_collision_key.hit_id
+                        ),
+                    ));
+                    if hover_pos.is_some_and(|pos| rect.contains(pos)) {
+                        hovered_boxes.push(format!(
+                            "Proximity box{}: hit ID {}, guard bit {}, collision type {}\n  \
+                            Makes the opponent block if they hold back inside it, but never \
+                            deals damage.\n  Stays at the origin the action started from \
+                            instead of following the character's movement.",
+                            object_label(attack_collision_key.object_id),
+                            attack_collision_key.hit_id,
+                            attack_collision_key.guard_bit,
+                            attack_collision_key.collision_type
+                        ));
+                    }
                 } else {
+                    let hitbox = offset_box(hitbox, attack_collision_key.hit_offset);
+                    let rect = self.box_rect(&hitbox, true, attack_collision_key.object_id);
+                    let guard_requirement = attack_collision_key.guard_requirement();
+                    let throw = attack_collision_key.is_throw();
+                    let (style, hit_id_color) = if throw {
+                        (self.draw_colors.throw, None)
+                    } else {
+                        (
+                            self.draw_colors.guard(guard_requirement),
+                            self.hit_id_color(attack_collision_key.hit_id),
+                        )
+                    };
                     painter.rect(
-                        Rect {
-                            min: Pos2 {
-                                x: hitbox.x.clone() - hitbox.width.clone()
-                                    + self.offset_x.clone()
-                                    + 0.5
-                                    + self.position.x
-                                    + self.root_motion.x,
-                                y: -hitbox.y.clone() - hitbox.height.clone()
-                                    + self.offset_y.clone()
-                                    + 0.5
-                                    - self.position.y
-                                    - self.root_motion.y,
-                            },
-                            max: Pos2 {
-                                x: hitbox.x.clone() + hitbox.width.clone() + self.offset_x.clone()
-                                    - 0.5
-                                    + self.position.x
-                                    + self.root_motion.x,
-                                y: -hitbox.y.clone()
-                                    + hitbox.height.clone()
-                                    + self.offset_y.clone()
-                                    - 0.5
-                                    - self.position.y
-                                    - self.root_motion.y,
-                            },
-                        },
-                        0.0,
-                        egui::Rgba::from_rgba_unmultiplied(0.8, 0.0, 0.0, 0.25),
+                        rect,
+                        self.box_rounding,
+                        style.fill,
                         Stroke {
-                            width: 1.0,
-                            color: Color32::RED,
+                            width: self.box_stroke_width,
+                            color: hit_id_color.unwrap_or(style.stroke),
                         },
-                    )
+                    );
+                    match hit_id_color {
+                        Some(color) => {
+                            painter.text(
+                                rect.right_top() + Vec2 { x: -2.0, y: 1.0 },
+                                Align2::RIGHT_TOP,
+                                attack_collision_key.hit_id.to_string(),
+                                FontId::monospace(10.0),
+                                color,
+                            );
+                        }
+                        None => (),
+                    }
+                    if self.show_disjoint && attack_collision_key.object_id == 0 {
+                        for disjoint_rect in subtract_rects(rect, &own_hurtbox_rects) {
+                            painter.rect_filled(
+                                disjoint_rect,
+                                0.0,
+                                Color32::from_rgba_unmultiplied(255, 255, 255, 90),
+                            );
+                        }
+                    }
+                    if throw {
+                        painter.text(
+                            rect.left_top() + Vec2 { x: 2.0, y: 1.0 },
+                            Align2::LEFT_TOP,
+                            "THROW",
+                            FontId::proportional(11.0),
+                            style.stroke,
+                        );
+                        // Horizontal gap to the dummy, in game units.
+                        let gap = (dummy_rect.min.x - rect.max.x)
+                            .max(rect.min.x - dummy_rect.max.x)
+                            .max(0.0)
+                            / self.zoom;
+                        throw_gap = Some(match throw_gap {
+                            Some(throw_gap) => throw_gap.min(gap),
+                            None => gap,
+                        });
+                    }
+                    if self.show_dummy && rect.intersects(dummy_rect) {
+                        if throw {
+                            dummy_thrown = true;
+                        } else {
+                            dummy_hit = true;
+                        }
+                        painter.rect_stroke(
+                            rect,
+                            self.box_rounding,
+                            Stroke {
+                                width: 3.0,
+                                color: Color32::WHITE,
+                            },
+                        );
+                    }
+                    box_rects.push(rect);
+                    box_labels.push((
+                        rect,
+                        format!("{} #{}", box_values(&hitbox), attack_collision_key.hit_id),
+                    ));
+                    if hover_pos.is_some_and(|pos| rect.contains(pos)) {
+                        hovered_boxes.push(format!(
+                            "{}{}: hit ID {}, guard bit {} ({}{}), collision type {}\n  \
+                            kind flag {:#x}",
+                            if throw { "Throw box" } else { "Hitbox" },
+                            object_label(attack_collision_key.object_id),
+                            attack_collision_key.hit_id,
+                            attack_collision_key.guard_bit,
+                            guard_requirement,
+                            if attack_collision_key.air_guardable() {
+                                ", air guardable"
+                            } else {
+                                ""
+                            },
+                            attack_collision_key.collision_type,
+                            attack_collision_key.kind_flag
+                        ));
+                        match &attack_collision_key.hit_data {
+                            Some(hit_data) => hovered_boxes.push(hit_data_to_string(hit_data)),
+                            None => (),
+                        }
+                    }
                 }
             }
         }
+        if self.show_diff {
+            self.render_diff_boxes(&painter);
+        }
+        if self.show_box_values {
+            let text_color = ui.visuals().text_color();
+            for (rect, label) in &box_labels {
+                painter.text(
+                    rect.left_top(),
+                    Align2::LEFT_BOTTOM,
+                    label,
+                    FontId::monospace(9.0),
+                    text_color,
+                );
+            }
+        }
+        if dummy_hit {
+            painter.text(
+                dummy_rect.center_top() - Vec2 { x: 0.0, y: 4.0 },
+                Align2::CENTER_BOTTOM,
+                "HIT",
+                FontId::proportional(16.0),
+                Color32::RED,
+            );
+        }
+        if self.show_dummy {
+            // Stacked above the HIT label when both apply.
+            let label_pos = dummy_rect.center_top()
+                - Vec2 {
+                    x: 0.0,
+                    y: if dummy_hit { 24.0 } else { 4.0 },
+                };
+            match throw_gap {
+                Some(_) if dummy_thrown => {
+                    painter.text(
+                        label_pos,
+                        Align2::CENTER_BOTTOM,
+                        "THROW",
+                        FontId::proportional(16.0),
+                        self.draw_colors.throw.stroke,
+                    );
+                }
+                Some(gap) => {
+                    painter.text(
+                        label_pos,
+                        Align2::CENTER_BOTTOM,
+                        format!("Out of throw range by {:.1}", gap),
+                        FontId::proportional(12.0),
+                        ui.visuals().weak_text_color(),
+                    );
+                }
+                None => (),
+            }
+        }
+        self.render_legend(&painter, response.rect);
+        self.canvas_rect = response.rect;
+        if self.png_export_requested || self.gif_capture_frame.is_some() {
+            // Only drawn on the frame that gets captured, so the image explains itself.
+            painter.text(
+                response.rect.left_bottom() + Vec2 { x: 8.0, y: -8.0 },
+                Align2::LEFT_BOTTOM,
+                self.frame_label(),
+                FontId::proportional(14.0),
+                ui.visuals().strong_text_color(),
+            );
+        }
+        match hover_pos {
+            Some(pos) if !self.png_export_requested && self.gif_capture_frame.is_none() => {
+                // Relative to where the character stands now, so hovering the tip of a box
+                // reads off its reach.
+                let (x, y) = self.screen_to_world(pos);
+                let mut readout = format!(
+                    "x {:.1}, y {:.1}",
+                    x - self.position.x - self.root_motion.x,
+                    y - self.position.y - self.root_motion.y
+                );
+                if self.show_dummy {
+                    readout += &format!(" ({:.1} from the dummy)", self.target_distance - x);
+                }
+                painter.text(
+                    response.rect.right_bottom() + Vec2 { x: -8.0, y: -8.0 },
+                    Align2::RIGHT_BOTTOM,
+                    readout,
+                    FontId::monospace(12.0),
+                    ui.visuals().text_color(),
+                );
+            }
+            _ => (),
+        }
+        let immune = self
+            .damage_collision_keys
+            .iter()
+            .filter(|damage_collision_key| damage_collision_key.object_id == 0)
+            .fold(0, |immune, damage_collision_key| {
+                immune | damage_collision_key.immune
+            });
+        if immune != 0 {
+            painter.text(
+                response.rect.left_top() + Vec2 { x: 8.0, y: 8.0 },
+                Align2::LEFT_TOP,
+                format!("INVULN: immune {:#04x}", immune),
+                FontId::proportional(16.0),
+                Color32::from_rgb(120, 200, 255),
+            );
+        }
+        match (self.measure_start, self.measure_end) {
+            (Some(start), Some(end)) => {
+                let start = snap_to_box_edge(start, &box_rects);
+                let end = snap_to_box_edge(end, &box_rects);
+                let stroke = Stroke {
+                    width: 1.0,
+                    color: ui.visuals().strong_text_color(),
+                };
+                painter.line_segment([start, end], stroke);
+                painter.circle_filled(start, 2.0, stroke.color);
+                painter.circle_filled(end, 2.0, stroke.color);
+                let delta = end - start;
+                let units = delta / self.zoom;
+                painter.text(
+                    end + Vec2 { x: 8.0, y: -8.0 },
+                    Align2::LEFT_BOTTOM,
+                    format!(
+                        "{:.1} units (x {:.1}, y {:.1}), {:.0} px",
+                        units.length(),
+                        units.x.abs(),
+                        units.y.abs(),
+                        delta.length()
+                    ),
+                    FontId::monospace(12.0),
+                    stroke.color,
+                );
+            }
+            _ => (),
+        }
+        if !hovered_boxes.is_empty() {
+            egui::show_tooltip_at_pointer(ui.ctx(), egui::Id::new("box_tooltip"), |ui| {
+                for hovered_box in &hovered_boxes {
+                    ui.label(hovered_box);
+                }
+            });
+        }
+        painter.circle(
+            self.world_to_screen(self.target_distance, 0.0),
+            4f32,
+            Color32::from_rgb(255, 140, 0),
+            Stroke::NONE,
+        );
         let mut visuals = ui.ctx().style().visuals.clone();
         if visuals.dark_mode {
             painter.circle(
-                Pos2 {
-                    x: self.position.x + self.root_motion.x + self.offset_x.clone(),
-                    y: -self.position.y - self.root_motion.y + self.offset_y.clone(),
-                },
+                self.world_to_screen(
+                    self.position.x + self.root_motion.x,
+                    self.position.y + self.root_motion.y,
+                ),
                 5f32,
                 Color32::GRAY,
                 Stroke {
@@ -1690,10 +5060,10 @@ impl Viewer {
             );
         } else {
             painter.circle(
-                Pos2 {
-                    x: self.position.x + self.root_motion.x + self.offset_x.clone(),
-                    y: -self.position.y - self.root_motion.y + self.offset_y.clone(),
-                },
+                self.world_to_screen(
+                    self.position.x + self.root_motion.x,
+                    self.position.y + self.root_motion.y,
+                ),
                 5f32,
                 Color32::GRAY,
                 Stroke {
@@ -1703,6 +5073,170 @@ impl Viewer {
             );
         }
 
-        response
+        if response.secondary_clicked() {
+            self.context_menu_pos = response.interact_pointer_pos();
+            self.context_menu_boxes = hovered_boxes;
+        }
+        response.context_menu(|ui| self.render_canvas_menu(ui))
+    }
+
+    fn render_canvas_menu(&mut self, ui: &mut egui::Ui) {
+        if ui.button("Reset view (Home)").clicked() {
+            self.reset_view();
+            ui.close_menu();
+        }
+        match self.context_menu_pos {
+            Some(pos) => {
+                if ui.button("Put dummy here").clicked() {
+                    let (x, _) = self.screen_to_world(pos);
+                    self.target_distance = (x + self.dummy_pushbox.x).clamp(0.0, 1000.0);
+                    self.show_dummy = true;
+                    self.invalidate_motion();
+                    ui.close_menu();
+                }
+            }
+            None => (),
+        }
+        if !self.context_menu_boxes.is_empty() && ui.button("Copy box info").clicked() {
+            ui.output_mut(|o| o.copied_text = self.context_menu_boxes.join("\n"));
+            ui.close_menu();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancel_flags_to_string_names_each_bit() {
+        assert_eq!(cancel_flags_to_string(0), "");
+        assert_eq!(cancel_flags_to_string(0b111), "Hit | Guard | Whiff");
+        assert_eq!(cancel_flags_to_string(1 << 5), "SuperJump");
+        assert_eq!(cancel_flags_to_string(1 << 6), "Defer");
+        assert_eq!(
+            cancel_flags_to_string(1 << 10 | 1 << 22),
+            "Counter | Terminator"
+        );
+        assert_eq!(cancel_flags_to_string(1 << 9), "0x200");
+        assert_eq!(cancel_flags_to_string(1 | 1 << 9 | 1 << 31), "Hit | 0x80000200");
+    }
+
+    #[test]
+    fn damage_type_flags_follow_the_raw_value_as_unverified_names() {
+        assert_eq!(unverified_names(0, &DAMAGE_TYPE_FLAGS), "");
+        assert_eq!(
+            unverified_names(0x1, &DAMAGE_TYPE_FLAGS),
+            " (unverified: counter)"
+        );
+        assert_eq!(
+            unverified_names(0x3, &DAMAGE_TYPE_FLAGS),
+            " (unverified: counter+punish counter)"
+        );
+        assert_eq!(
+            unverified_names(0x12, &DAMAGE_TYPE_FLAGS),
+            " (unverified: punish counter+0x10)"
+        );
+    }
+
+    #[test]
+    fn set_ignore_skips_later_keys_on_the_same_value() {
+        let mut ignored = [false; 6];
+        let keys = [
+            (SteerOperationType::Add, SteerValueType::VelocityX),
+            (SteerOperationType::SetIgnore, SteerValueType::VelocityX),
+            (SteerOperationType::Set, SteerValueType::VelocityX),
+            (SteerOperationType::Set, SteerValueType::VelocityY),
+        ];
+        let applied: Vec<bool> = keys
+            .iter()
+            .map(|(op_type, value_type)| steer_key_applies(&mut ignored, op_type, value_type))
+            .collect();
+        assert_eq!(applied, vec![true, false, false, true]);
+    }
+
+    #[test]
+    fn inherit_keys_carry_over_the_previous_value() {
+        assert_eq!(
+            steer_key_to_value(SteerOperationType::SetInherit, 0.0, 3.5, 1.0),
+            3.5
+        );
+        assert_eq!(
+            steer_key_to_value(SteerOperationType::SetInheritXYZ, 0.0, -2.0, 1.0),
+            -2.0
+        );
+    }
+
+    #[test]
+    fn reset_motion_clears_inherited_motion() {
+        let mut viewer = Viewer {
+            inherits_motion: true,
+            ..Default::default()
+        };
+        viewer.reset_motion();
+        assert!(!viewer.inherits_motion);
+    }
+
+    #[test]
+    fn start_motion_seeds_the_followed_action_only() {
+        let carried = MotionState {
+            velocity: Vector3f {
+                x: 3f32,
+                y: 5f32,
+                z: 0f32,
+            },
+            acceleration: Vector3f {
+                x: 0f32,
+                y: -1f32,
+                z: 0f32,
+            },
+            ..Default::default()
+        };
+        let mut viewer = Viewer {
+            selected_index: 4,
+            carried_motion: Some((4, carried)),
+            ..Default::default()
+        };
+        viewer.start_motion();
+        assert_eq!((viewer.prev_velocity.x, viewer.prev_velocity.y), (3f32, 5f32));
+        assert_eq!(viewer.prev_acceleration.y, -1f32);
+        assert_eq!((viewer.velocity.x, viewer.velocity.y), (0f32, 0f32));
+
+        viewer.selected_index = 5;
+        viewer.start_motion();
+        assert!(viewer.carried_motion.is_none());
+        assert_eq!((viewer.prev_velocity.x, viewer.prev_velocity.y), (0f32, 0f32));
+    }
+
+    #[test]
+    fn landing_frame_finds_the_end_of_a_parabola() {
+        let mut viewer = Viewer {
+            velocity: Vector3f {
+                x: 0f32,
+                y: 4f32,
+                z: 0f32,
+            },
+            gravity: 2f32,
+            // Movement stops on the action's end frame, so keep that past the jump.
+            action_info: ActionInfo {
+                end_frame: 20,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        for frame in 0..10 {
+            viewer.update_position(frame);
+        }
+        // Gravity only pulls once the character is off the ground, so the heights are 4, 8,
+        // 10, 10, 8, 4 and then -2 on the update of key frame 6.
+        assert_eq!(viewer.landing_frame, Some(8));
+        assert_eq!(viewer.position.y, 0f32);
+    }
+
+    #[test]
+    fn landing_frame_ignores_grounded_characters() {
+        assert_eq!(landing_frame(3, false, -5f32, 0f32), None);
+        assert_eq!(landing_frame(3, true, 1f32, 0f32), None);
+        assert_eq!(landing_frame(3, true, 9f32, 10f32), Some(5));
     }
 }
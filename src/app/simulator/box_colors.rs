@@ -0,0 +1,187 @@
+use crate::extract::{GuardRequirement, HurtboxRegion};
+use eframe::egui::{self, Color32};
+use serde::{Deserialize, Serialize};
+
+/// Guard requirements in legend order.
+pub const GUARD_REQUIREMENTS: [GuardRequirement; 5] = [
+    GuardRequirement::Mid,
+    GuardRequirement::Overhead,
+    GuardRequirement::Low,
+    GuardRequirement::Air,
+    GuardRequirement::Unblockable,
+];
+
+pub const HURTBOX_REGIONS: [HurtboxRegion; 3] =
+    [HurtboxRegion::Head, HurtboxRegion::Body, HurtboxRegion::Leg];
+
+/// Fill and outline of one kind of box.
+#[derive(Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct BoxStyle {
+    pub fill: Color32,
+    pub stroke: Color32,
+}
+
+impl BoxStyle {
+    fn new(r: u8, g: u8, b: u8, fill_alpha: u8) -> Self {
+        Self {
+            fill: Color32::from_rgba_unmultiplied(r, g, b, fill_alpha),
+            stroke: Color32::from_rgb(r, g, b),
+        }
+    }
+
+    /// Moves both colors `amount` of the way towards `target`, keeping their alpha.
+    fn shade(&self, target: Color32, amount: f32) -> Self {
+        let mix = |color: Color32| {
+            let channel =
+                |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * amount) as u8;
+            let [r, g, b, a] = color.to_srgba_unmultiplied();
+            Color32::from_rgba_unmultiplied(
+                channel(r, target.r()),
+                channel(g, target.g()),
+                channel(b, target.b()),
+                a,
+            )
+        };
+        Self {
+            fill: mix(self.fill),
+            stroke: mix(self.stroke),
+        }
+    }
+
+    /// A more opaque fill and a darker outline, which stand out against a light background.
+    fn on_light_background(&self) -> Self {
+        let [r, g, b, a] = self.fill.to_srgba_unmultiplied();
+        Self {
+            fill: Color32::from_rgba_unmultiplied(r, g, b, (a as f32 * 1.75).min(255.0) as u8),
+            stroke: self.shade(Color32::BLACK, 0.35).stroke,
+        }
+    }
+}
+
+/// The colors boxes are drawn with. Hitboxes are colored by what it takes to block them.
+#[derive(Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BoxColors {
+    pub pushbox: BoxStyle,
+    pub hurtbox: BoxStyle,
+    pub throw_hurtbox: BoxStyle,
+    pub proximity: BoxStyle,
+    pub mid: BoxStyle,
+    pub overhead: BoxStyle,
+    pub low: BoxStyle,
+    pub air: BoxStyle,
+    pub unblockable: BoxStyle,
+    pub throw: BoxStyle,
+}
+
+impl Default for BoxColors {
+    fn default() -> Self {
+        Self {
+            pushbox: BoxStyle::new(255, 255, 0, 64),
+            hurtbox: BoxStyle::new(0, 255, 0, 64),
+            throw_hurtbox: BoxStyle::new(0, 220, 220, 64),
+            proximity: BoxStyle::new(160, 160, 160, 26),
+            mid: BoxStyle::new(255, 0, 0, 64),
+            overhead: BoxStyle::new(255, 140, 0, 64),
+            low: BoxStyle::new(170, 60, 255, 64),
+            air: BoxStyle::new(80, 160, 255, 64),
+            unblockable: BoxStyle::new(150, 0, 0, 64),
+            throw: BoxStyle::new(255, 60, 220, 64),
+        }
+    }
+}
+
+impl BoxColors {
+    /// The Okabe-Ito palette, which stays distinguishable with the common kinds of color
+    /// blindness.
+    pub fn colorblind() -> Self {
+        Self {
+            pushbox: BoxStyle::new(240, 228, 66, 64),
+            hurtbox: BoxStyle::new(0, 158, 115, 64),
+            throw_hurtbox: BoxStyle::new(86, 180, 233, 64),
+            proximity: BoxStyle::new(160, 160, 160, 26),
+            mid: BoxStyle::new(213, 94, 0, 64),
+            overhead: BoxStyle::new(230, 159, 0, 64),
+            low: BoxStyle::new(204, 121, 167, 64),
+            air: BoxStyle::new(0, 114, 178, 64),
+            unblockable: BoxStyle::new(110, 40, 0, 64),
+            throw: BoxStyle::new(235, 235, 235, 64),
+        }
+    }
+
+    /// The colors to draw with on the current theme. The colors are picked for the dark theme,
+    /// so in light mode they get adjusted to keep their contrast.
+    pub fn for_background(&self, dark_mode: bool) -> Self {
+        if dark_mode {
+            return self.clone();
+        }
+        Self {
+            pushbox: self.pushbox.on_light_background(),
+            hurtbox: self.hurtbox.on_light_background(),
+            throw_hurtbox: self.throw_hurtbox.on_light_background(),
+            proximity: self.proximity.on_light_background(),
+            mid: self.mid.on_light_background(),
+            overhead: self.overhead.on_light_background(),
+            low: self.low.on_light_background(),
+            air: self.air.on_light_background(),
+            unblockable: self.unblockable.on_light_background(),
+            throw: self.throw.on_light_background(),
+        }
+    }
+
+    pub fn guard(&self, guard_requirement: GuardRequirement) -> BoxStyle {
+        match guard_requirement {
+            GuardRequirement::Mid => self.mid,
+            GuardRequirement::Overhead => self.overhead,
+            GuardRequirement::Low => self.low,
+            GuardRequirement::Air => self.air,
+            GuardRequirement::Unblockable => self.unblockable,
+        }
+    }
+
+    /// Body hurtboxes use the hurtbox color as is, with head hurtboxes a lighter shade of it and
+    /// leg hurtboxes a darker one.
+    pub fn hurtbox_region(&self, region: HurtboxRegion) -> BoxStyle {
+        match region {
+            HurtboxRegion::Head => self.hurtbox.shade(Color32::WHITE, 0.45),
+            HurtboxRegion::Body => self.hurtbox,
+            HurtboxRegion::Leg => self.hurtbox.shade(Color32::BLACK, 0.4),
+        }
+    }
+
+    /// Color pickers for every kind of box, plus the presets.
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("box_colors").show(ui, |ui| {
+            ui.label("");
+            ui.label("Fill");
+            ui.label("Outline");
+            ui.end_row();
+            let rows = [
+                ("Pushbox", &mut self.pushbox),
+                ("Hurtbox", &mut self.hurtbox),
+                ("Throw hurtbox", &mut self.throw_hurtbox),
+                ("Proximity box", &mut self.proximity),
+                ("Hitbox (mid)", &mut self.mid),
+                ("Hitbox (overhead)", &mut self.overhead),
+                ("Hitbox (low)", &mut self.low),
+                ("Hitbox (air)", &mut self.air),
+                ("Hitbox (unblockable)", &mut self.unblockable),
+                ("Throw box", &mut self.throw),
+            ];
+            for (name, style) in rows {
+                ui.label(name);
+                ui.color_edit_button_srgba(&mut style.fill);
+                ui.color_edit_button_srgba(&mut style.stroke);
+                ui.end_row();
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui.button("Default").clicked() {
+                *self = Self::default();
+            }
+            if ui.button("Colorblind").clicked() {
+                *self = Self::colorblind();
+            }
+        });
+    }
+}
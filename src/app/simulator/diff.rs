@@ -0,0 +1,640 @@
+use super::{box_values, offset_box};
+use crate::extract::{
+    self, AttackCollisionKey, CollisionBox, DamageCollisionKey, FrameBoxes, HitData,
+    PushCollisionKey,
+};
+use sf6_rsz_parser::fchar::CharacterAsset;
+use std::fmt::Display;
+
+/// Boxes closer than this on every value are treated as the same box.
+const BOX_TOLERANCE: f32 = 0.001;
+
+#[derive(strum_macros::Display, PartialEq, Eq, Clone, Copy)]
+pub enum BoxKind {
+    Pushbox,
+    Hurtbox,
+    #[strum(serialize = "Throw hurtbox")]
+    ThrowHurtbox,
+    Hitbox,
+    #[strum(serialize = "Proximity box")]
+    ProximityBox,
+}
+
+#[derive(Clone, PartialEq)]
+pub enum BoxChangeKind {
+    /// Only in the loaded file.
+    Added,
+    /// Only in the comparison file.
+    Removed,
+    /// Moved or resized from this box in the comparison file.
+    Changed(CollisionBox),
+}
+
+/// A box that differs between the two files on a frame.
+pub struct BoxChange {
+    /// 0-based frame of the action.
+    pub frame: usize,
+    pub kind: BoxKind,
+    pub object_id: usize,
+    /// The box in the loaded file, or in the comparison file if it was removed. Hitboxes
+    /// already have their hit offset applied.
+    pub collision_box: CollisionBox,
+    pub change: BoxChangeKind,
+}
+
+impl BoxChange {
+    /// Proximity boxes stay at the origin, same as when they're drawn normally.
+    pub fn follows_position(&self) -> bool {
+        self.kind != BoxKind::ProximityBox
+    }
+}
+
+/// A changed value of a key or its hit data over a run of frames.
+pub struct KeyChange {
+    /// 0-based frames of the action, inclusive.
+    pub first_frame: usize,
+    pub last_frame: usize,
+    pub text: String,
+}
+
+/// What changed in one action from the comparison file to the loaded file.
+#[derive(Default)]
+pub struct ActionDiff {
+    /// The comparison file has no action with the same ID.
+    pub missing: bool,
+    /// Changed values of the action, one line each.
+    pub changes: Vec<String>,
+    pub key_changes: Vec<KeyChange>,
+    pub boxes: Vec<BoxChange>,
+}
+
+impl ActionDiff {
+    pub fn is_empty(&self) -> bool {
+        !self.missing
+            && self.changes.is_empty()
+            && self.key_changes.is_empty()
+            && self.boxes.is_empty()
+    }
+
+    pub fn frame_boxes(&self, frame: usize) -> impl Iterator<Item = &BoxChange> {
+        self.boxes
+            .iter()
+            .filter(move |box_change| box_change.frame == frame)
+    }
+}
+
+fn same_box(a: &CollisionBox, b: &CollisionBox) -> bool {
+    (a.x - b.x).abs() < BOX_TOLERANCE
+        && (a.y - b.y).abs() < BOX_TOLERANCE
+        && (a.width - b.width).abs() < BOX_TOLERANCE
+        && (a.height - b.height).abs() < BOX_TOLERANCE
+}
+
+/// Flattens a frame into comparable (kind, object, box) entries.
+fn flatten_boxes(frame_boxes: &FrameBoxes) -> Vec<(BoxKind, usize, CollisionBox)> {
+    let mut boxes = vec![];
+    for push_collision_key in &frame_boxes.push_collision_keys {
+        boxes.push((
+            BoxKind::Pushbox,
+            push_collision_key.object_id,
+            push_collision_key.pushbox.clone(),
+        ));
+    }
+    for damage_collision_key in &frame_boxes.damage_collision_keys {
+        for hurtbox in damage_collision_key.hurtboxes() {
+            boxes.push((
+                BoxKind::Hurtbox,
+                damage_collision_key.object_id,
+                hurtbox.clone(),
+            ));
+        }
+        for throw_box in &damage_collision_key.throw_boxes {
+            boxes.push((
+                BoxKind::ThrowHurtbox,
+                damage_collision_key.object_id,
+                throw_box.clone(),
+            ));
+        }
+    }
+    for attack_collision_key in &frame_boxes.attack_collision_keys {
+        for hitbox in &attack_collision_key.boxes {
+            if attack_collision_key.is_proximity() {
+                boxes.push((
+                    BoxKind::ProximityBox,
+                    attack_collision_key.object_id,
+                    hitbox.clone(),
+                ));
+            } else {
+                boxes.push((
+                    BoxKind::Hitbox,
+                    attack_collision_key.object_id,
+                    offset_box(hitbox, attack_collision_key.hit_offset),
+                ));
+            }
+        }
+    }
+    boxes
+}
+
+/// Every entry of `boxes` without a matching entry in `others`. Each entry of `others` can
+/// only match once, so a duplicated box still counts.
+fn unmatched(
+    boxes: &[(BoxKind, usize, CollisionBox)],
+    others: &[(BoxKind, usize, CollisionBox)],
+) -> Vec<usize> {
+    let mut used = vec![false; others.len()];
+    let mut unmatched = vec![];
+    for (index, (kind, object_id, collision_box)) in boxes.iter().enumerate() {
+        let found = others.iter().enumerate().position(
+            |(other_index, (other_kind, other_object_id, other_box))| {
+                !used[other_index]
+                    && kind == other_kind
+                    && object_id == other_object_id
+                    && same_box(collision_box, other_box)
+            },
+        );
+        match found {
+            Some(other_index) => used[other_index] = true,
+            None => unmatched.push(index),
+        }
+    }
+    unmatched
+}
+
+/// Pairs removed and added boxes of the same kind and object that kept either their position or
+/// their size, so a resized or moved box is one change instead of a removal and an addition.
+fn box_changes(
+    frame: usize,
+    removed: Vec<&(BoxKind, usize, CollisionBox)>,
+    added: Vec<&(BoxKind, usize, CollisionBox)>,
+) -> Vec<BoxChange> {
+    let mut changes = vec![];
+    let mut paired = vec![false; added.len()];
+    for (kind, object_id, previous_box) in removed {
+        let pair = added.iter().enumerate().position(
+            |(added_index, (added_kind, added_object_id, added_box))| {
+                let same_position = (added_box.x - previous_box.x).abs() < BOX_TOLERANCE
+                    && (added_box.y - previous_box.y).abs() < BOX_TOLERANCE;
+                let same_size = (added_box.width - previous_box.width).abs() < BOX_TOLERANCE
+                    && (added_box.height - previous_box.height).abs() < BOX_TOLERANCE;
+                !paired[added_index]
+                    && added_kind == kind
+                    && added_object_id == object_id
+                    && (same_position || same_size)
+            },
+        );
+        changes.push(match pair {
+            Some(added_index) => {
+                paired[added_index] = true;
+                BoxChange {
+                    frame,
+                    kind: *kind,
+                    object_id: *object_id,
+                    collision_box: added[added_index].2.clone(),
+                    change: BoxChangeKind::Changed(previous_box.clone()),
+                }
+            }
+            None => BoxChange {
+                frame,
+                kind: *kind,
+                object_id: *object_id,
+                collision_box: previous_box.clone(),
+                change: BoxChangeKind::Removed,
+            },
+        });
+    }
+    for (added_index, (kind, object_id, collision_box)) in added.into_iter().enumerate() {
+        if !paired[added_index] {
+            changes.push(BoxChange {
+                frame,
+                kind: *kind,
+                object_id: *object_id,
+                collision_box: collision_box.clone(),
+                change: BoxChangeKind::Added,
+            });
+        }
+    }
+    changes
+}
+
+/// Pairs up the keys of two frames by object and by their order within it, as
+/// (object, order, key in the comparison file, key in the loaded file).
+fn pair_keys<'a, K>(
+    keys: &'a [K],
+    other_keys: &'a [K],
+    object_id: fn(&K) -> usize,
+) -> Vec<(usize, usize, &'a K, &'a K)> {
+    let mut pairs = vec![];
+    let mut seen: Vec<usize> = vec![];
+    for key in keys {
+        let id = object_id(key);
+        let order = seen.iter().filter(|seen_id| **seen_id == id).count();
+        seen.push(id);
+        if let Some(other_key) = other_keys
+            .iter()
+            .filter(|other_key| object_id(other_key) == id)
+            .nth(order)
+        {
+            pairs.push((id, order, other_key, key));
+        }
+    }
+    pairs
+}
+
+fn key_label(kind: &str, object_id: usize, order: usize) -> String {
+    match object_id {
+        0 => format!("{} key {}", kind, order + 1),
+        object_id => format!("{} key {} on object {}", kind, order + 1, object_id),
+    }
+}
+
+/// The values of a key that the diff compares, by name.
+type Fields = Vec<(String, String)>;
+
+fn field(name: &str, value: impl Display) -> (String, String) {
+    (name.to_string(), value.to_string())
+}
+
+fn push_key_fields(key: &PushCollisionKey) -> Fields {
+    vec![
+        field("condition", key.condition),
+        field("attribute", format!("{:#06x}", key.attribute)),
+    ]
+}
+
+fn damage_key_fields(key: &DamageCollisionKey) -> Fields {
+    vec![
+        field("condition", key.condition),
+        field("collision type", key.collision_type),
+        field("immune", format!("{:#04x}", key.immune)),
+        field("extend", key.extend),
+        field("level", key.level),
+        field("type flag", format!("{:#010x}", key.type_flag)),
+    ]
+}
+
+fn attack_key_fields(key: &AttackCollisionKey) -> Fields {
+    let mut fields = vec![
+        field("condition", key.condition),
+        field("collision type", key.collision_type),
+        field("hit ID", key.hit_id),
+        field("guard bit", format!("{:#04x}", key.guard_bit)),
+        field("kind flag", format!("{:#x}", key.kind_flag)),
+        field(
+            "hit offset",
+            format!("{}, {}", key.hit_offset[0], key.hit_offset[1]),
+        ),
+    ];
+    if let Some(hit_data) = &key.hit_data {
+        fields.append(&mut hit_data_fields(hit_data));
+    }
+    fields
+}
+
+fn hit_data_fields(hit_data: &HitData) -> Fields {
+    let mut fields = vec![
+        field("hitstop (self)", hit_data.hit_stop_owner),
+        field("hitstop (opponent)", hit_data.hit_stop_target),
+    ];
+    for (situation, damage) in &hit_data.damage {
+        fields.push(field(&format!("damage #{}", situation), damage));
+    }
+    for reaction in &hit_data.reactions {
+        let name = |value: &str| format!("reaction #{} {}", reaction.situation, value);
+        fields.push(field(&name("stun"), reaction.time));
+        fields.push(field(
+            &name("pushback"),
+            format!("{}, {}", reaction.dest_x, reaction.dest_y),
+        ));
+    }
+    fields
+}
+
+/// Occurrences of each name in `fields`, as (name, how many times it came before), so fields
+/// that share a name, such as two reactions for one situation, are matched in order.
+fn field_occurrences(fields: &Fields) -> Vec<(&str, usize)> {
+    let mut occurrences: Vec<(&str, usize)> = vec![];
+    for (name, _) in fields {
+        let count = occurrences
+            .iter()
+            .filter(|(other, _)| other == name)
+            .count();
+        occurrences.push((name, count));
+    }
+    occurrences
+}
+
+/// Adds "label name: before → after" to `changes` for every value that differs. A value only
+/// one side has shows as "-" on the other.
+fn diff_fields(changes: &mut Vec<String>, label: &str, before: &Fields, after: &Fields) {
+    let value = |fields: &Fields, name: &str, occurrence: usize| match fields
+        .iter()
+        .filter(|(other, _)| other == name)
+        .nth(occurrence)
+    {
+        Some((_, value)) => value.clone(),
+        None => "-".to_string(),
+    };
+    let mut names = field_occurrences(before);
+    for name in field_occurrences(after) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    for (name, occurrence) in names {
+        let before = value(before, name, occurrence);
+        let after = value(after, name, occurrence);
+        if before != after {
+            changes.push(format!("{} {}: {} → {}", label, name, before, after));
+        }
+    }
+}
+
+/// The changed key values of one frame, one line each.
+fn diff_keys(other_frame_boxes: &FrameBoxes, frame_boxes: &FrameBoxes) -> Vec<String> {
+    let mut changes = vec![];
+    for (object_id, order, before, after) in pair_keys(
+        &frame_boxes.push_collision_keys,
+        &other_frame_boxes.push_collision_keys,
+        |key| key.object_id,
+    ) {
+        let label = key_label("Pushbox", object_id, order);
+        diff_fields(
+            &mut changes,
+            &label,
+            &push_key_fields(before),
+            &push_key_fields(after),
+        );
+    }
+    for (object_id, order, before, after) in pair_keys(
+        &frame_boxes.damage_collision_keys,
+        &other_frame_boxes.damage_collision_keys,
+        |key| key.object_id,
+    ) {
+        let label = key_label("Hurtbox", object_id, order);
+        diff_fields(
+            &mut changes,
+            &label,
+            &damage_key_fields(before),
+            &damage_key_fields(after),
+        );
+    }
+    for (object_id, order, before, after) in pair_keys(
+        &frame_boxes.attack_collision_keys,
+        &other_frame_boxes.attack_collision_keys,
+        |key| key.object_id,
+    ) {
+        let label = key_label("Hitbox", object_id, order);
+        diff_fields(
+            &mut changes,
+            &label,
+            &attack_key_fields(before),
+            &attack_key_fields(after),
+        );
+    }
+    changes
+}
+
+/// Compares two actions frame by frame, merging a key change that lasts several frames into
+/// one run.
+fn diff_frames(frames: &[FrameBoxes], other_frames: &[FrameBoxes], diff: &mut ActionDiff) {
+    let empty: FrameBoxes = Default::default();
+    for frame in 0..frames.len().max(other_frames.len()) {
+        let frame_boxes = frames.get(frame).unwrap_or(&empty);
+        let other_frame_boxes = other_frames.get(frame).unwrap_or(&empty);
+        let boxes = flatten_boxes(frame_boxes);
+        let other_boxes = flatten_boxes(other_frame_boxes);
+        let removed = unmatched(&other_boxes, &boxes)
+            .into_iter()
+            .map(|index| &other_boxes[index])
+            .collect();
+        let added = unmatched(&boxes, &other_boxes)
+            .into_iter()
+            .map(|index| &boxes[index])
+            .collect();
+        diff.boxes.append(&mut box_changes(frame, removed, added));
+
+        for text in diff_keys(other_frame_boxes, frame_boxes) {
+            match diff
+                .key_changes
+                .iter_mut()
+                .find(|key_change| key_change.text == text && key_change.last_frame + 1 == frame)
+            {
+                Some(key_change) => key_change.last_frame = frame,
+                None => diff.key_changes.push(KeyChange {
+                    first_frame: frame,
+                    last_frame: frame,
+                    text,
+                }),
+            }
+        }
+    }
+}
+
+fn frame_count(fchar: &CharacterAsset, action_index: usize) -> usize {
+    fchar.action_list[action_index]
+        .info
+        .action_data
+        .frames
+        .max(0) as usize
+}
+
+/// Compares the action at `action_index` with the action of the same ID in `other`.
+pub fn diff_action(
+    fchar: &CharacterAsset,
+    action_index: usize,
+    other: &CharacterAsset,
+) -> ActionDiff {
+    let mut diff: ActionDiff = Default::default();
+    let action_id = fchar.action_list[action_index].info.action_data.action_id;
+    let other_index = match other
+        .action_list
+        .iter()
+        .position(|action| action.info.action_data.action_id == action_id)
+    {
+        Some(other_index) => other_index,
+        None => {
+            diff.missing = true;
+            return diff;
+        }
+    };
+
+    let frames = frame_count(fchar, action_index);
+    let other_frames = frame_count(other, other_index);
+    let mut changes = vec![("Frames", other_frames as i32, frames as i32)];
+    let action_info = extract::get_action_info(fchar, action_index);
+    let other_action_info = extract::get_action_info(other, other_index);
+    changes.push((
+        "First active frame",
+        other_action_info.first_active_frame,
+        action_info.first_active_frame,
+    ));
+    changes.push((
+        "Recovery frame",
+        other_action_info.recovery_frame,
+        action_info.recovery_frame,
+    ));
+    changes.push((
+        "End frame",
+        other_action_info.end_frame,
+        action_info.end_frame,
+    ));
+    changes.push((
+        "Loop count",
+        other_action_info.loop_count,
+        action_info.loop_count,
+    ));
+    for (name, before, after) in changes {
+        if before != after {
+            diff.changes
+                .push(format!("{}: {} → {}", name, before, after));
+        }
+    }
+
+    let frames: Vec<FrameBoxes> = (0..frames)
+        .map(|frame| extract::get_boxes(fchar, action_index, frame as i32))
+        .collect();
+    let other_frames: Vec<FrameBoxes> = (0..other_frames)
+        .map(|frame| extract::get_boxes(other, other_index, frame as i32))
+        .collect();
+    diff_frames(&frames, &other_frames, &mut diff);
+    diff
+}
+
+/// One changelog line for a box change, with the frame counted from 1 as displayed.
+pub fn box_change_to_string(box_change: &BoxChange) -> String {
+    let object = match box_change.object_id {
+        0 => "".to_string(),
+        object_id => format!(" on object {}", object_id),
+    };
+    let values = box_values(&box_change.collision_box);
+    match &box_change.change {
+        BoxChangeKind::Added => format!(
+            "Frame {}: {} {}{} added",
+            box_change.frame + 1,
+            box_change.kind,
+            values,
+            object
+        ),
+        BoxChangeKind::Removed => format!(
+            "Frame {}: {} {}{} removed",
+            box_change.frame + 1,
+            box_change.kind,
+            values,
+            object
+        ),
+        BoxChangeKind::Changed(previous_box) => format!(
+            "Frame {}: {}{} changed from {} to {}",
+            box_change.frame + 1,
+            box_change.kind,
+            object,
+            box_values(previous_box),
+            values
+        ),
+    }
+}
+
+/// One changelog line for a key change, with frames counted from 1 as displayed.
+pub fn key_change_to_string(key_change: &KeyChange) -> String {
+    if key_change.first_frame == key_change.last_frame {
+        return format!("Frame {}: {}", key_change.first_frame + 1, key_change.text);
+    }
+    format!(
+        "Frames {}-{}: {}",
+        key_change.first_frame + 1,
+        key_change.last_frame + 1,
+        key_change.text
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collision_box(x: f32, y: f32, width: f32, height: f32) -> CollisionBox {
+        CollisionBox {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn frame_with_hitbox(collision_box: CollisionBox, kind_flag: u32) -> FrameBoxes {
+        FrameBoxes {
+            attack_collision_keys: vec![AttackCollisionKey {
+                kind_flag,
+                boxes: vec![collision_box],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn unmatched_counts_each_duplicate_once() {
+        let hurtbox = (BoxKind::Hurtbox, 0, collision_box(0.0, 50.0, 20.0, 50.0));
+        let boxes = vec![hurtbox.clone(), hurtbox.clone()];
+        assert_eq!(unmatched(&boxes, std::slice::from_ref(&hurtbox)), vec![1]);
+        let throw_hurtbox = (BoxKind::ThrowHurtbox, 0, hurtbox.2.clone());
+        assert_eq!(unmatched(&[hurtbox], &[throw_hurtbox]), vec![0]);
+    }
+
+    #[test]
+    fn diff_frames_shows_a_resized_box_as_one_change() {
+        let before = [frame_with_hitbox(collision_box(40.0, 60.0, 20.0, 10.0), 0)];
+        let after = [frame_with_hitbox(collision_box(40.0, 60.0, 30.0, 10.0), 0)];
+        let mut diff: ActionDiff = Default::default();
+        diff_frames(&after, &before, &mut diff);
+        assert_eq!(diff.boxes.len(), 1);
+        assert!(
+            diff.boxes[0].change
+                == BoxChangeKind::Changed(before[0].attack_collision_keys[0].boxes[0].clone())
+        );
+        assert_eq!(diff.boxes[0].collision_box.width, 30.0);
+        assert!(diff.key_changes.is_empty());
+    }
+
+    #[test]
+    fn diff_frames_lists_added_and_removed_boxes() {
+        let before = [frame_with_hitbox(collision_box(40.0, 60.0, 20.0, 10.0), 0)];
+        let after = [frame_with_hitbox(collision_box(90.0, 0.0, 5.0, 5.0), 0)];
+        let mut diff: ActionDiff = Default::default();
+        diff_frames(&after, &before, &mut diff);
+        let changes: Vec<&BoxChangeKind> = diff
+            .boxes
+            .iter()
+            .map(|box_change| &box_change.change)
+            .collect();
+        assert!(changes == vec![&BoxChangeKind::Removed, &BoxChangeKind::Added]);
+    }
+
+    #[test]
+    fn diff_frames_merges_a_key_change_over_several_frames() {
+        let hitbox = collision_box(40.0, 60.0, 20.0, 10.0);
+        let before: Vec<FrameBoxes> = (0..3)
+            .map(|_| frame_with_hitbox(hitbox.clone(), 0x1))
+            .collect();
+        let mut after: Vec<FrameBoxes> = [0x3, 0x3, 0x1]
+            .into_iter()
+            .map(|kind_flag| frame_with_hitbox(hitbox.clone(), kind_flag))
+            .collect();
+        after[0].attack_collision_keys[0].hit_data = Some(HitData {
+            damage: vec![(0, 800)],
+            ..Default::default()
+        });
+        let mut diff: ActionDiff = Default::default();
+        diff_frames(&after, &before, &mut diff);
+        assert!(diff.boxes.is_empty());
+        let lines: Vec<String> = diff.key_changes.iter().map(key_change_to_string).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "Frames 1-2: Hitbox key 1 kind flag: 0x1 → 0x3",
+                "Frame 1: Hitbox key 1 hitstop (self): - → 0",
+                "Frame 1: Hitbox key 1 hitstop (opponent): - → 0",
+                "Frame 1: Hitbox key 1 damage #0: - → 800",
+            ]
+        );
+    }
+}
@@ -0,0 +1,31 @@
+//! `sf6_dump <fchar> [--action N]` prints frame data as JSON. It's a console program of its own
+//! because the viewer is built as a Windows GUI app in release, which has nowhere to print to.
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let usage = "Usage: sf6_dump <fchar> [--action N]";
+    let path = match args.get(1) {
+        Some(path) if path != "--action" => path,
+        _ => {
+            eprintln!("{}", usage);
+            std::process::exit(1);
+        }
+    };
+    let action = match args.iter().position(|arg| arg == "--action") {
+        Some(action_index) => match args.get(action_index + 1).map(|arg| arg.parse::<usize>()) {
+            Some(Ok(action)) => Some(action),
+            _ => {
+                eprintln!("{}", usage);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+    match sf6_sim::extract::dump(path, action) {
+        Ok(json) => println!("{}", json),
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
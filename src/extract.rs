@@ -0,0 +1,2184 @@
+//! Box, cancel and movement data read out of a parsed fchar, with no UI attached. The viewer
+//! and `sf6_dump` are both built on top of these functions.
+
+pub mod action_names;
+
+use bitvec::order::Lsb0;
+use bitvec::view::BitView;
+use include_bytes_zstd::include_bytes_zstd;
+use num_derive::FromPrimitive;
+use serde::{Deserialize, Serialize};
+use sf6_rsz_parser::fchar::{CharacterAsset, DataId};
+use sf6_rsz_parser::parse_fchar;
+use sf6_rsz_parser::rsz::json_parser::parse_json;
+use sf6_rsz_parser::rsz::RSZValue;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+#[derive(Default, FromPrimitive, PartialEq, Eq, Clone)]
+pub enum SteerOperationType {
+    #[default]
+    Nop = 0,
+    Set = 1,
+    Add = 2,
+    Multiply = 3,
+    SetSign = 4,
+    AddSign = 5,
+    SetNegativeX = 6,
+    SetNegativeY = 7,
+    SetNegativeZ = 8,
+    SetMinimum = 9,
+    SetMaximum = 10,
+    SetIgnore = 11,
+    SetInherit = 12,
+    SetTarget = 13,
+    SetHomingValue = 14,
+    SetHomingTime = 15,
+    SetInheritXYZ = 16,
+}
+
+#[derive(Default, FromPrimitive, PartialEq, Eq, Clone)]
+pub enum SteerValueType {
+    #[default]
+    VelocityX = 0,
+    VelocityY = 1,
+    VelocityZ = 2,
+    AccelerationX = 3,
+    AccelerationY = 4,
+    AccelerationZ = 5,
+}
+
+#[derive(Default, Clone, PartialEq, Serialize)]
+pub struct CollisionBox {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+#[derive(Default, Clone, PartialEq, Serialize)]
+pub struct PushCollisionKey {
+    pub condition: u8,
+    pub attribute: u16,
+    pub pushbox: CollisionBox,
+    /// Which entry of the action's `objects` the key belongs to. 0 is the character itself.
+    pub object_id: usize,
+}
+
+#[derive(Default, Clone, PartialEq, Serialize)]
+pub struct DamageCollisionKey {
+    pub condition: u8,
+    pub collision_type: u8,
+    pub immune: u8,
+    pub extend: u8,
+    pub level: u8,
+    pub type_flag: u32,
+    pub head_boxes: Vec<CollisionBox>,
+    pub body_boxes: Vec<CollisionBox>,
+    pub leg_boxes: Vec<CollisionBox>,
+    pub throw_boxes: Vec<CollisionBox>,
+    pub object_id: usize,
+}
+
+#[derive(Default, Clone, PartialEq, Serialize)]
+pub struct AttackCollisionKey {
+    pub condition: u8,
+    pub collision_type: u8,
+    pub hit_id: i8,
+    pub guard_bit: u8,
+    pub kind_flag: u32,
+    pub hit_offset: [i32; 2],
+    pub boxes: Vec<CollisionBox>,
+    pub object_id: usize,
+    pub hit_data: Option<HitData>,
+}
+
+/// How the opponent reacts in one hit situation. The game picks the situation (e.g. normal
+/// hit, counter hit, guard) by `situation`, which is the record's IndexID.
+#[derive(Default, Clone, PartialEq, Serialize)]
+pub struct HitReaction {
+    pub situation: u8,
+    /// Hitstun or blockstun.
+    pub time: u8,
+    /// Pushback.
+    pub dest_x: i16,
+    pub dest_y: i16,
+}
+
+/// Damage, hitstop and reactions of an attack, resolved from the HitInfo table.
+#[derive(Default, Clone, PartialEq, Serialize)]
+pub struct HitData {
+    /// Damage per hit situation.
+    pub damage: Vec<(u8, u16)>,
+    pub hit_stop_owner: u8,
+    pub hit_stop_target: i8,
+    pub reactions: Vec<HitReaction>,
+}
+
+/// The `collision_type` of attack keys that throw instead of strike. The bundled files only
+/// use it for grabs: Ryu has one such key, his throw, while grapplers like Manon and Zangief
+/// have dozens for their command grabs.
+pub const COLLISION_TYPE_THROW: u8 = 2;
+/// The `collision_type` of proximity boxes, which don't hit but make the opponent guard when
+/// they're inside one.
+pub const COLLISION_TYPE_PROXIMITY: u8 = 3;
+
+/// How an attack has to be blocked, decoded from `guard_bit`. Bit 0 allows a standing guard,
+/// bit 1 a crouching guard and bit 2 an air guard.
+#[derive(strum_macros::Display, PartialEq, Eq, Clone, Copy)]
+pub enum GuardRequirement {
+    Mid,
+    Overhead,
+    Low,
+    Air,
+    Unblockable,
+}
+
+/// Guessed meanings of the bits of a damage key's `immune` field. An entry covering several
+/// bits is used when all of them are set. The bundled files also set bits these don't name
+/// (0x80 and 0x0b both occur).
+pub const IMMUNE_FLAGS: [(u32, &str); 4] = [
+    (0b111, "full"),
+    (1 << 0, "strike"),
+    (1 << 1, "throw"),
+    (1 << 2, "projectile"),
+];
+
+/// Guessed names for the bits of a push key's `attribute` field. The bundled files only ever
+/// set bits 0 and 1.
+pub const PUSH_ATTRIBUTE_FLAGS: [(u32, &str); 3] = [
+    (1 << 0, "crouching"),
+    (1 << 1, "airborne"),
+    (1 << 2, "throw immune"),
+];
+
+/// Guessed names for the bits of a damage key's `type_flag` field. Most hurtboxes in the
+/// bundled files have 0x3, so they don't obviously fit.
+pub const DAMAGE_TYPE_FLAGS: [(u32, &str); 2] = [(1 << 0, "counter"), (1 << 1, "punish counter")];
+
+/// Names `flag`'s bits after `names`, e.g. "strike+throw" with a `separator` of "+", with any
+/// bits left over as hex. An entry only matches when all of its bits are set, and bits it
+/// matched aren't named again. Returns None when no bit is set.
+pub fn flags_to_string(flag: u32, names: &[(u32, &str)], separator: &str) -> Option<String> {
+    if flag == 0 {
+        return None;
+    }
+    let mut remaining = flag;
+    let mut parts: Vec<String> = vec![];
+    for (bits, name) in names {
+        if remaining & bits == *bits {
+            parts.push(name.to_string());
+            remaining &= !bits;
+        }
+    }
+    if remaining != 0 {
+        parts.push(format!("{:#x}", remaining));
+    }
+    Some(parts.join(separator))
+}
+
+/// Which of a damage key's lists a hurtbox came from.
+#[derive(strum_macros::Display, PartialEq, Eq, Clone, Copy)]
+pub enum HurtboxRegion {
+    Head,
+    Body,
+    Leg,
+}
+
+impl DamageCollisionKey {
+    /// The hurtbox lists by region, head first.
+    pub fn regions(&self) -> [(HurtboxRegion, &Vec<CollisionBox>); 3] {
+        [
+            (HurtboxRegion::Head, &self.head_boxes),
+            (HurtboxRegion::Body, &self.body_boxes),
+            (HurtboxRegion::Leg, &self.leg_boxes),
+        ]
+    }
+
+    /// Every strike hurtbox, regardless of region.
+    pub fn hurtboxes(&self) -> impl Iterator<Item = &CollisionBox> {
+        self.head_boxes
+            .iter()
+            .chain(&self.body_boxes)
+            .chain(&self.leg_boxes)
+    }
+}
+
+impl AttackCollisionKey {
+    pub fn guard_requirement(&self) -> GuardRequirement {
+        match self.guard_bit & 0b11 {
+            0b11 => GuardRequirement::Mid,
+            0b01 => GuardRequirement::Overhead,
+            0b10 => GuardRequirement::Low,
+            _ => {
+                if self.guard_bit & 0b100 > 0 {
+                    GuardRequirement::Air
+                } else {
+                    GuardRequirement::Unblockable
+                }
+            }
+        }
+    }
+
+    pub fn air_guardable(&self) -> bool {
+        self.guard_bit & 0b100 > 0
+    }
+
+    /// Throws, command grabs included, have a collision type of their own.
+    pub fn is_throw(&self) -> bool {
+        self.collision_type == COLLISION_TYPE_THROW
+    }
+
+    pub fn is_proximity(&self) -> bool {
+        self.collision_type == COLLISION_TYPE_PROXIMITY
+    }
+}
+
+#[derive(Default, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub struct Trigger {
+    pub action: i32,
+    pub condition_flag: u32,
+    /// Set if the trigger's command has a charge step.
+    pub charge: Option<ChargeRequirement>,
+}
+
+/// A charge step of a command, from the character's charge table.
+#[derive(Default, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub struct ChargeRequirement {
+    /// Frames the direction has to be held.
+    pub frames: i16,
+    /// Frames the charge is kept after letting go.
+    pub keep_frames: i16,
+    /// The direction to hold, as lever bits.
+    pub lever: u8,
+}
+
+/// A BranchKey: while it is active, the action can move on to `action`.
+#[derive(Default, Clone, Serialize)]
+pub struct Branch {
+    /// Key frames the branch is active on, end exclusive.
+    pub start_frame: i32,
+    pub end_frame: i32,
+    /// Action ID to switch to, and the frame of it to start from.
+    pub action: i32,
+    pub action_frame: i32,
+    pub branch_type: u8,
+    pub trigger_id: i32,
+}
+
+#[derive(Default)]
+pub struct ActionInfo {
+    pub first_active_frame: i32,
+    pub recovery_frame: i32,
+    pub end_frame: i32,
+    pub loop_count: i32,
+}
+
+/// What exists on one frame of the action, for the timeline strip.
+#[derive(Default, Clone)]
+pub struct TimelineCell {
+    pub attack: bool,
+    /// The `kind_flag` bits of every attack key on this frame.
+    pub attack_kind_flag: u32,
+    pub hurtbox: bool,
+    pub pushbox_change: bool,
+    /// A damage key on this frame sets any `immune` bits.
+    pub invulnerable: bool,
+    /// The `immune` bits of every damage key on this frame.
+    pub immune: u8,
+}
+
+/// Everything resolved for one frame of an action.
+#[derive(Default)]
+pub struct FrameBoxes {
+    pub push_collision_keys: Vec<PushCollisionKey>,
+    pub damage_collision_keys: Vec<DamageCollisionKey>,
+    pub attack_collision_keys: Vec<AttackCollisionKey>,
+    pub triggers: Vec<Trigger>,
+    /// Box indices that keys on this frame point at but that aren't in their table.
+    pub missing_boxes: Vec<MissingBox>,
+}
+
+impl FrameBoxes {
+    /// Whether both frames have the same boxes and cancels, ignoring where the character is.
+    pub fn same_events(&self, other: &FrameBoxes) -> bool {
+        self.push_collision_keys == other.push_collision_keys
+            && self.damage_collision_keys == other.damage_collision_keys
+            && self.attack_collision_keys == other.attack_collision_keys
+            && self.triggers == other.triggers
+    }
+}
+
+/// A box index referenced by a key that couldn't be found in its `DataId` table.
+#[derive(Clone, Serialize)]
+pub struct MissingBox {
+    pub table: &'static str,
+    pub index: i32,
+}
+
+/// The key frame shown as `displayed_frame`. Key frames count from 0 while the viewer counts
+/// displayed frames from 1, so a key on key frames 0 to 2 shows on frames 1 to 3.
+pub fn key_frame(displayed_frame: usize) -> i32 {
+    displayed_frame as i32 - 1
+}
+
+/// Whether a key with these start and end frames is active on key frame `frame`. The end frame
+/// is exclusive.
+pub fn key_active(start_frame: i32, end_frame: i32, frame: i32) -> bool {
+    start_frame <= frame && end_frame > frame
+}
+
+/// Decodes a trigger group's bitset into trigger IDs. Each word holds 64 triggers, so
+/// bit `n` of word `w` is trigger `w * 64 + n`.
+pub fn trigger_ids(triggers: &[u64]) -> Vec<usize> {
+    let mut ids: Vec<usize> = vec![];
+    for (trigger_index, select_trigger) in triggers.iter().enumerate() {
+        for bit_index in select_trigger.view_bits::<Lsb0>().iter_ones() {
+            ids.push(bit_index + trigger_index * 64);
+        }
+    }
+    ids
+}
+
+pub fn steer_key_to_value(
+    op_type: SteerOperationType,
+    in_value: f32,
+    prev_value: f32,
+    modify_value: f32,
+) -> f32 {
+    let mut value = in_value;
+    match op_type {
+        SteerOperationType::Nop => {}
+        SteerOperationType::Set => value = modify_value,
+        SteerOperationType::Add => value += modify_value,
+        SteerOperationType::Multiply => value *= modify_value,
+        SteerOperationType::SetSign => {}
+        SteerOperationType::AddSign => {}
+        SteerOperationType::SetNegativeX
+        | SteerOperationType::SetNegativeY
+        | SteerOperationType::SetNegativeZ => {
+            if (value < 0f32 && prev_value > 0f32) || (value > 0f32 && prev_value < 0f32) {
+                value = modify_value;
+            }
+        }
+        SteerOperationType::SetMinimum => {
+            if value < modify_value {
+                value = modify_value;
+            }
+        }
+        SteerOperationType::SetMaximum => {
+            if value > modify_value {
+                value = modify_value;
+            }
+        }
+        // The caller skips the value's later keys on the same frame.
+        SteerOperationType::SetIgnore => {}
+        SteerOperationType::SetInherit => value = prev_value,
+        SteerOperationType::SetTarget => {}
+        SteerOperationType::SetHomingValue => {}
+        SteerOperationType::SetHomingTime => {}
+        // The other two axes are carried over by the caller.
+        SteerOperationType::SetInheritXYZ => value = prev_value,
+    }
+    value
+}
+
+/// Reads the frame counts from an action's ActionFrame and ActionState data.
+pub fn get_action_info(fchar: &CharacterAsset, action_index: usize) -> ActionInfo {
+    let mut action_info: ActionInfo = Default::default();
+    let action = &fchar.action_list[action_index];
+    let action_frame = &action.action.data[0];
+    let first_active_frame = &action_frame.fields[0].value;
+    match first_active_frame {
+        RSZValue::Int32(frame) => action_info.first_active_frame = frame.clone(),
+        _ => (),
+    }
+    let recovery_frame = &action_frame.fields[1].value;
+    match recovery_frame {
+        RSZValue::Int32(frame) => action_info.recovery_frame = frame.clone(),
+        _ => (),
+    }
+    let end_frame = &action_frame.fields[2].value;
+    match end_frame {
+        RSZValue::Int32(frame) => action_info.end_frame = frame.clone(),
+        _ => (),
+    }
+    let action_state = &action.action.data[1];
+    let loop_count = &action_state.fields[0].value;
+    match loop_count {
+        RSZValue::Int32(count) => action_info.loop_count = count.clone(),
+        _ => (),
+    }
+    action_info
+}
+
+/// An animation clip the action plays, from one of its MotionKeys.
+#[derive(Default, Clone, Serialize)]
+pub struct MotionClip {
+    pub object_id: usize,
+    /// Key frames the clip plays on, end exclusive.
+    pub start_frame: i32,
+    pub end_frame: i32,
+    /// The motion bank the clip is in, and its ID in the bank.
+    pub motion_type: i32,
+    pub motion_id: i32,
+    /// The part of the clip that gets played, in frames of the clip.
+    pub motion_start_frame: i16,
+    pub motion_end_frame: i16,
+}
+
+/// Every MotionKey of an action, plain or with extra flags, in key order.
+pub fn get_motion_clips(fchar: &CharacterAsset, action_index: usize) -> Vec<MotionClip> {
+    let mut motion_clips: Vec<MotionClip> = vec![];
+    let action = &fchar.action_list[action_index];
+    for (object_id, object) in action.objects.iter().enumerate() {
+        for (index, object_index) in object.action.object_table.iter().enumerate() {
+            let data = &object.action.data[object_index.clone() as usize - 1];
+            match data.name.as_str() {
+                "CharacterAsset.MotionKey"
+                | "CharacterAsset.SimpleMotionKey"
+                | "CharacterAsset.ExtMotionKey" => (),
+                _ => continue,
+            }
+            let key_data = &object.info.object_data.key_data[index];
+            let mut motion_clip = MotionClip {
+                object_id,
+                start_frame: key_data.key_start_frame,
+                end_frame: key_data.key_end_frame,
+                ..Default::default()
+            };
+            match &data.fields[0].value {
+                RSZValue::Int32(int) => motion_clip.motion_type = int.clone(),
+                _ => (),
+            }
+            match &data.fields[1].value {
+                RSZValue::Int32(int) => motion_clip.motion_id = int.clone(),
+                _ => (),
+            }
+            match &data.fields[2].value {
+                RSZValue::Int16(short) => motion_clip.motion_start_frame = short.clone(),
+                _ => (),
+            }
+            match &data.fields[3].value {
+                RSZValue::Int16(short) => motion_clip.motion_end_frame = short.clone(),
+                _ => (),
+            }
+            motion_clips.push(motion_clip);
+        }
+    }
+    motion_clips
+}
+
+/// Every BranchKey of an action, in key order.
+pub fn get_branches(fchar: &CharacterAsset, action_index: usize) -> Vec<Branch> {
+    let mut branches: Vec<Branch> = vec![];
+    let action = &fchar.action_list[action_index];
+    for object in &action.objects {
+        for (index, object_index) in object.action.object_table.iter().enumerate() {
+            let data = &object.action.data[object_index.clone() as usize - 1];
+            if data.name != "CharacterAsset.BranchKey" {
+                continue;
+            }
+            let key_data = &object.info.object_data.key_data[index];
+            let mut branch = Branch {
+                start_frame: key_data.key_start_frame,
+                end_frame: key_data.key_end_frame,
+                ..Default::default()
+            };
+            match &data.fields[1].value {
+                RSZValue::Int32(int) => branch.action = int.clone(),
+                _ => (),
+            }
+            match &data.fields[2].value {
+                RSZValue::Int32(int) => branch.action_frame = int.clone(),
+                _ => (),
+            }
+            match &data.fields[4].value {
+                RSZValue::UInt8(ubyte) => branch.branch_type = ubyte.clone(),
+                _ => (),
+            }
+            match &data.fields[5].value {
+                RSZValue::Int32(int) => branch.trigger_id = int.clone(),
+                _ => (),
+            }
+            branches.push(branch);
+        }
+    }
+    branches
+}
+
+/// An Int2 field as `[x, y]`, such as an AttackCollisionKey's HitOffset. Other types read
+/// as no offset.
+fn int2_to_array(value: &RSZValue) -> [i32; 2] {
+    match value {
+        RSZValue::Int2(int2) => [int2.x, int2.y],
+        _ => [0; 2],
+    }
+}
+
+/// Box coordinates are usually Int16, but some entries store them as Int32 or Float.
+fn box_field_to_f32(value: &RSZValue) -> f32 {
+    match value {
+        RSZValue::Int16(short) => *short as f32,
+        RSZValue::Int32(int) => *int as f32,
+        RSZValue::Float(float) => *float,
+        _ => 0f32,
+    }
+}
+
+/// The numbers in a box record, in order. Some records hold x, y, width and height directly,
+/// others wrap them in nested objects, such as a position and a size, so objects are read
+/// depth-first. Lists aren't coordinates and are skipped.
+fn box_coordinates(values: &[&RSZValue]) -> Vec<f32> {
+    let mut coordinates: Vec<f32> = vec![];
+    for value in values {
+        match value {
+            RSZValue::Object(object) => {
+                let fields: Vec<&RSZValue> =
+                    object.fields.iter().map(|field| &field.value).collect();
+                coordinates.append(&mut box_coordinates(&fields));
+            }
+            RSZValue::List(_) => (),
+            _ => coordinates.push(box_field_to_f32(value)),
+        }
+    }
+    coordinates
+}
+
+/// Looks up hit data record `int` in the HitInfo table. A record is an AttackDataBase with its
+/// parameter objects stored before it, so everything between the previous record's root and
+/// this one belongs to it.
+pub fn get_hit_data(fchar: &CharacterAsset, int: i32) -> Option<HitData> {
+    let table = fchar
+        .data_id_table
+        .iter()
+        .rposition(|data_id| *data_id == DataId::HitInfo)?;
+    let range = data_list_entry(fchar, table, int as u32)?;
+    let mut hit_data: HitData = Default::default();
+    for instance in &fchar.data_list_table[table].data_rsz.data[range] {
+        let value = |index: usize| instance.fields.get(index).map(|field| &field.value);
+        let situation = match value(0) {
+            Some(RSZValue::UInt8(ubyte)) => *ubyte,
+            _ => 0,
+        };
+        match instance.name.as_str() {
+            "CharacterAsset.Params.Gauge" => {
+                if let Some(RSZValue::UInt16(ushort)) = value(1) {
+                    hit_data.damage.push((situation, *ushort));
+                }
+            }
+            "CharacterAsset.Params.Timer" => {
+                if let Some(RSZValue::UInt8(ubyte)) = value(1) {
+                    hit_data.hit_stop_owner = *ubyte;
+                }
+                if let Some(RSZValue::Int8(byte)) = value(2) {
+                    hit_data.hit_stop_target = *byte;
+                }
+            }
+            "CharacterAsset.Params.Behavior"
+            | "CharacterAsset.Params.BehaviorA"
+            | "CharacterAsset.Params.BehaviorB"
+            | "CharacterAsset.Params.BehaviorC"
+            | "CharacterAsset.Params.BehaviorD"
+            | "CharacterAsset.Params.BehaviorG"
+            | "CharacterAsset.Params.BehaviorKarma"
+            | "CharacterAsset.Params.BehaviorP" => {
+                let mut reaction = HitReaction {
+                    situation,
+                    ..Default::default()
+                };
+                if let Some(RSZValue::Int16(short)) = value(7) {
+                    reaction.dest_x = *short;
+                }
+                if let Some(RSZValue::Int16(short)) = value(8) {
+                    reaction.dest_y = *short;
+                }
+                if let Some(RSZValue::UInt8(ubyte)) = value(10) {
+                    reaction.time = *ubyte;
+                }
+                hit_data.reactions.push(reaction);
+            }
+            _ => (),
+        }
+    }
+    Some(hit_data)
+}
+
+/// Looks up box `int` in the table for `data_type` and appends it to `boxes`. Returns false
+/// if there's no such table or box.
+pub fn index_to_box(
+    fchar: &CharacterAsset,
+    int: i32,
+    data_type: DataId,
+    boxes: &mut Vec<CollisionBox>,
+) -> bool {
+    let data_list = match fchar
+        .data_id_table
+        .iter()
+        .rposition(|data_id| *data_id == data_type)
+        .and_then(|data_index| fchar.data_list_table.get(data_index))
+    {
+        Some(data_list) => data_list,
+        None => return false,
+    };
+    let index = match data_list
+        .data_ids
+        .iter()
+        .rposition(|value| *value == int as u32)
+    {
+        Some(index) => index + 1,
+        None => return false,
+    };
+    let data = match data_list.data_rsz.data.get(index * 6 - 1) {
+        Some(data) => data,
+        None => return false,
+    };
+    let values: Vec<&RSZValue> = data.fields.iter().map(|field| &field.value).collect();
+    let coordinates = box_coordinates(&values);
+    let coordinate = |n: usize| coordinates.get(n).copied().unwrap_or_default();
+    let x = coordinate(0);
+    let y = coordinate(1);
+    let width = coordinate(2);
+    let height = coordinate(3);
+    let collision_box: CollisionBox = CollisionBox {
+        x,
+        y,
+        width,
+        height,
+    };
+    boxes.push(collision_box);
+    true
+}
+
+fn data_id_name(data_type: &DataId) -> &'static str {
+    match data_type {
+        DataId::HurtBox => "HurtBox",
+        DataId::ThrowHurtBox => "ThrowHurtBox",
+        DataId::ProximityBox => "ProximityBox",
+        DataId::StrikeBox => "StrikeBox",
+        _ => "Unknown",
+    }
+}
+
+/// [`index_to_box`], recording the index in `missing` when it can't be resolved.
+fn resolve_box(
+    fchar: &CharacterAsset,
+    int: i32,
+    data_type: DataId,
+    boxes: &mut Vec<CollisionBox>,
+    missing: &mut Vec<MissingBox>,
+) {
+    let table = data_id_name(&data_type);
+    if !index_to_box(fchar, int, data_type, boxes) {
+        missing.push(MissingBox { table, index: int });
+    }
+}
+
+fn get_triggers(fchar: &CharacterAsset, group: i32, condition_flag: u32) -> Vec<Trigger> {
+    let mut triggers: Vec<Trigger> = vec![];
+    let mut data_index: usize = 0;
+    for (n, data_id) in fchar.data_id_table.iter().enumerate() {
+        match data_id {
+            DataId::TriggerGroup => data_index = n,
+            _ => (),
+        }
+    }
+    let mut index: usize = 0;
+    for (n, value) in fchar.data_list_table[data_index]
+        .data_ids
+        .iter()
+        .enumerate()
+    {
+        if value.clone() == group as u32 {
+            index = n;
+        }
+    }
+    if index == 0 {
+        return triggers;
+    }
+    let mut trigger_words: Vec<u64> = vec![];
+    let trigger_group = &fchar.data_list_table[data_index].data_rsz.data[index];
+    match &trigger_group.fields[1].value {
+        RSZValue::List(list) => {
+            for select_trigger in list {
+                match select_trigger {
+                    RSZValue::UInt64(ulong) => {
+                        trigger_words.push(ulong.clone());
+                    }
+                    _ => (),
+                }
+            }
+        }
+        _ => (),
+    }
+    let data_index = fchar
+        .data_id_table
+        .iter()
+        .rposition(|data_id| matches!(data_id, DataId::Trigger))
+        .unwrap_or(0);
+    let data_ids = &fchar.data_list_table[data_index].data_ids;
+    let data = &fchar.data_list_table[data_index].data_rsz.data;
+    for trigger_id in trigger_ids(&trigger_words) {
+        let mut index: usize = 0;
+        for (n, value) in data_ids.iter().enumerate() {
+            if value.clone() == trigger_id as u32 {
+                index = n + 1;
+            }
+        }
+        // A trigger ID that isn't in the table only loses that trigger.
+        if index == 0 {
+            continue;
+        }
+        let trigger = match data.get(index * 17 - 1) {
+            Some(trigger) => trigger,
+            None => continue,
+        };
+        let mut stored_trigger: Trigger = Default::default();
+        stored_trigger.condition_flag = condition_flag;
+        if let Some(RSZValue::Int32(action)) = trigger.fields.get(5).map(|field| &field.value) {
+            stored_trigger.action = *action;
+        }
+        // The trigger's own instances come right before it, with the Normal input first.
+        let normal_input = data
+            .get(index * 17 - 17..index * 17 - 1)
+            .unwrap_or_default()
+            .iter()
+            .find(|data| data.name == "CharacterAsset.TriggerInput");
+        match normal_input.and_then(|input| input.fields.get(8)) {
+            Some(field) => match &field.value {
+                RSZValue::Int32(command_no) => {
+                    stored_trigger.charge = get_command_charge(fchar, command_no.clone())
+                }
+                _ => (),
+            },
+            None => (),
+        }
+        triggers.push(stored_trigger);
+    }
+    triggers
+}
+
+/// The data list table whose entries have a `root_class` instance as their root, such as
+/// "CharacterAsset.ChargeParamSub" for the charge table.
+fn find_data_list(fchar: &CharacterAsset, root_class: &str) -> Option<usize> {
+    fchar.data_list_table.iter().position(|data_list| {
+        data_list
+            .data_rsz
+            .data
+            .last()
+            .map(|data| data.name.as_str())
+            == Some(root_class)
+    })
+}
+
+/// Where entry `id` of data list table `table` lies in the table's instances. An entry's
+/// parameter objects are stored before its root, so it runs from the previous entry's root up
+/// to its own. None if there's no such entry or its roots are out of bounds.
+fn data_list_entry(fchar: &CharacterAsset, table: usize, id: u32) -> Option<Range<usize>> {
+    let data_list = fchar.data_list_table.get(table)?;
+    let index = data_list.data_ids.iter().position(|value| *value == id)?;
+    let root = *data_list.data_rsz.object_table.get(index)? as usize;
+    let start = match index {
+        0 => 0,
+        _ => data_list.data_rsz.object_table[index - 1] as usize,
+    };
+    if root == 0 || start >= root || root > data_list.data_rsz.data.len() {
+        return None;
+    }
+    Some(start..root)
+}
+
+/// The charge step of command `command_no`, if it has one. Steps reference the charge table by
+/// ID, and a step with no charge has an ID that isn't in it.
+fn get_command_charge(fchar: &CharacterAsset, command_no: i32) -> Option<ChargeRequirement> {
+    let table = find_data_list(fchar, "CharacterAsset.CommandParamSub")?;
+    let range = data_list_entry(fchar, table, command_no as u32)?;
+    fchar.data_list_table[table].data_rsz.data[range]
+        .iter()
+        .filter(|instance| instance.name == "CharacterAsset.CommandParamN")
+        .find_map(|instance| {
+            let charge_id = field_to_i32(&instance.fields.get(2)?.value)?;
+            if charge_id < 0 {
+                return None;
+            }
+            get_charge(fchar, charge_id)
+        })
+}
+
+/// Looks up entry `charge_id` of the charge table.
+fn get_charge(fchar: &CharacterAsset, charge_id: i32) -> Option<ChargeRequirement> {
+    let table = find_data_list(fchar, "CharacterAsset.ChargeParamSub")?;
+    let range = data_list_entry(fchar, table, charge_id as u32)?;
+    let instances = &fchar.data_list_table[table].data_rsz.data[range];
+    let charge_param = &instances[instances.len() - 1];
+    let field = |index: usize| match charge_param.fields.get(index) {
+        Some(field) => field_to_i32(&field.value).unwrap_or_default(),
+        None => 0,
+    };
+    let mut charge = ChargeRequirement {
+        frames: field(0) as i16,
+        keep_frames: field(1) as i16,
+        lever: 0,
+    };
+    if charge.frames <= 0 {
+        return None;
+    }
+    // OKPara is the first key under the entry.
+    if let Some(field) = instances
+        .iter()
+        .find(|instance| instance.name == "CharacterAsset.ChargeParamKey")
+        .and_then(|key| key.fields.first())
+    {
+        charge.lever = field_to_i32(&field.value).unwrap_or_default() as u8;
+    }
+    Some(charge)
+}
+
+/// Cancels available on key frame `frame` of an action, sorted and without duplicates.
+pub fn get_trigger_keys(fchar: &CharacterAsset, action_index: usize, frame: i32) -> Vec<Trigger> {
+    let mut triggers: Vec<Trigger> = vec![];
+    let mut groups: Vec<i32> = vec![];
+    let mut condition_flags: Vec<u32> = vec![];
+    let action = &fchar.action_list[action_index];
+    for object in &action.objects {
+        for (index, object_index) in object.action.object_table.iter().enumerate() {
+            let key_data = &object.info.object_data.key_data[index];
+            if key_active(key_data.key_start_frame, key_data.key_end_frame, frame) {
+                let data = &object.action.data[object_index.clone() as usize - 1];
+                match data.name.as_str() {
+                    "CharacterAsset.TriggerKey" => {
+                        let group = &data.fields[0].value;
+                        match group {
+                            RSZValue::Int32(group) => {
+                                groups.push(group.clone());
+                            }
+                            _ => (),
+                        }
+                        let condition_flag = &data.fields[1].value;
+                        match condition_flag {
+                            RSZValue::UInt32(condition_flag) => {
+                                condition_flags.push(condition_flag.clone());
+                            }
+                            _ => (),
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+    for (index, group) in groups.iter().enumerate() {
+        triggers.append(&mut get_triggers(
+            fchar,
+            group.clone(),
+            condition_flags[index],
+        ));
+    }
+    triggers.sort_unstable();
+    triggers.dedup();
+    triggers
+}
+
+/// A run of frames where an action can cancel into `action`.
+#[derive(Clone)]
+pub struct CancelWindow {
+    pub action: i32,
+    /// Displayed frames, like active windows.
+    pub first_frame: usize,
+    pub last_frame: usize,
+}
+
+/// Every cancel window of an action, in the order they open. Built from the triggers of each
+/// frame, so TriggerKeys that overlap or follow on from each other make a single window.
+pub fn get_cancel_windows(fchar: &CharacterAsset, action_index: usize) -> Vec<CancelWindow> {
+    let frames = fchar.action_list[action_index]
+        .info
+        .action_data
+        .frames
+        .max(0) as usize;
+    let mut cancel_windows: Vec<CancelWindow> = vec![];
+    let mut open_windows: Vec<CancelWindow> = vec![];
+    for frame in 0..frames {
+        let mut actions: Vec<i32> = get_trigger_keys(fchar, action_index, frame as i32)
+            .iter()
+            .map(|trigger| trigger.action)
+            .collect();
+        actions.dedup();
+        let mut still_open: Vec<CancelWindow> = vec![];
+        for cancel_window in open_windows {
+            if actions.contains(&cancel_window.action) {
+                still_open.push(cancel_window);
+            } else {
+                cancel_windows.push(cancel_window);
+            }
+        }
+        open_windows = still_open;
+        for action in actions {
+            match open_windows
+                .iter_mut()
+                .find(|cancel_window| cancel_window.action == action)
+            {
+                Some(cancel_window) => cancel_window.last_frame = frame + 1,
+                None => open_windows.push(CancelWindow {
+                    action,
+                    first_frame: frame + 1,
+                    last_frame: frame + 1,
+                }),
+            }
+        }
+    }
+    cancel_windows.append(&mut open_windows);
+    cancel_windows.sort_by_key(|cancel_window| cancel_window.first_frame);
+    cancel_windows
+}
+
+/// Every cancel window in the file, by the action ID it cancels into. Each window's `action`
+/// is the action it's in instead, so this answers what can cancel into an action.
+pub fn get_cancel_sources(fchar: &CharacterAsset) -> HashMap<i32, Vec<CancelWindow>> {
+    let mut cancel_sources: HashMap<i32, Vec<CancelWindow>> = HashMap::new();
+    for (action_index, action) in fchar.action_list.iter().enumerate() {
+        let action_id = action.info.action_data.action_id;
+        for cancel_window in get_cancel_windows(fchar, action_index) {
+            cancel_sources
+                .entry(cancel_window.action)
+                .or_default()
+                .push(CancelWindow {
+                    action: action_id,
+                    ..cancel_window
+                });
+        }
+    }
+    cancel_sources
+}
+
+/// Boxes active on key frame `frame` of an action. `triggers` is left empty; see
+/// [`get_trigger_keys`].
+pub fn get_boxes(fchar: &CharacterAsset, action_index: usize, frame: i32) -> FrameBoxes {
+    let mut frame_boxes: FrameBoxes = Default::default();
+    let action = &fchar.action_list[action_index];
+    for (object_id, object) in action.objects.iter().enumerate() {
+        for (index, object_index) in object.action.object_table.iter().enumerate() {
+            let key_data = &object.info.object_data.key_data[index];
+            if key_active(key_data.key_start_frame, key_data.key_end_frame, frame) {
+                let data = &object.action.data[object_index.clone() as usize - 1];
+                match data.name.as_str() {
+                    "CharacterAsset.PushCollisionKey" => {
+                        let mut boxes: Vec<CollisionBox> = vec![];
+                        let mut pushbox: CollisionBox = Default::default();
+
+                        let mut condition = 0u8;
+                        match &data.fields[0].value {
+                            RSZValue::UInt8(ubyte) => condition = ubyte.clone(),
+                            _ => (),
+                        }
+                        let mut attribute = 0u16;
+                        match &data.fields[1].value {
+                            RSZValue::UInt16(ushort) => attribute = ushort.clone(),
+                            _ => (),
+                        }
+                        match &data.fields[2].value {
+                            // Pushboxes are kept in the throw hurtbox table.
+                            RSZValue::Int32(int) => {
+                                if !index_to_box(fchar, *int, DataId::ThrowHurtBox, &mut boxes) {
+                                    frame_boxes.missing_boxes.push(MissingBox {
+                                        table: "PushBox",
+                                        index: *int,
+                                    });
+                                }
+                            }
+                            _ => (),
+                        }
+
+                        if !boxes.is_empty() {
+                            pushbox = boxes[0].clone();
+                        }
+
+                        let push_collision = PushCollisionKey {
+                            condition,
+                            attribute,
+                            pushbox,
+                            object_id,
+                        };
+                        frame_boxes.push_collision_keys.push(push_collision)
+                    }
+                    "CharacterAsset.DamageCollisionKey" => {
+                        let mut head_boxes: Vec<CollisionBox> = vec![];
+                        let mut body_boxes: Vec<CollisionBox> = vec![];
+                        let mut leg_boxes: Vec<CollisionBox> = vec![];
+
+                        let mut head_list: &Vec<RSZValue> = &vec![];
+                        match &data.fields[9].value {
+                            RSZValue::List(list) => head_list = list,
+                            _ => (),
+                        }
+                        for head_index in head_list {
+                            match head_index {
+                                RSZValue::Int32(int) => {
+                                    resolve_box(
+                                        fchar,
+                                        int.clone(),
+                                        DataId::HurtBox,
+                                        &mut head_boxes,
+                                        &mut frame_boxes.missing_boxes,
+                                    );
+                                }
+                                _ => (),
+                            }
+                        }
+                        let mut body_list: &Vec<RSZValue> = &vec![];
+                        match &data.fields[10].value {
+                            RSZValue::List(list) => body_list = list,
+                            _ => (),
+                        }
+                        for body_index in body_list {
+                            match body_index {
+                                RSZValue::Int32(int) => {
+                                    resolve_box(
+                                        fchar,
+                                        int.clone(),
+                                        DataId::HurtBox,
+                                        &mut body_boxes,
+                                        &mut frame_boxes.missing_boxes,
+                                    );
+                                }
+                                _ => (),
+                            }
+                        }
+                        let mut leg_list: &Vec<RSZValue> = &vec![];
+                        match &data.fields[11].value {
+                            RSZValue::List(list) => leg_list = list,
+                            _ => (),
+                        }
+                        for leg_index in leg_list {
+                            match leg_index {
+                                RSZValue::Int32(int) => {
+                                    resolve_box(
+                                        fchar,
+                                        int.clone(),
+                                        DataId::HurtBox,
+                                        &mut leg_boxes,
+                                        &mut frame_boxes.missing_boxes,
+                                    );
+                                }
+                                _ => (),
+                            }
+                        }
+                        let mut throw_boxes: Vec<CollisionBox> = vec![];
+                        let mut throw_list: &Vec<RSZValue> = &vec![];
+                        match &data.fields[12].value {
+                            RSZValue::List(list) => throw_list = list,
+                            _ => (),
+                        }
+                        for throw_index in throw_list {
+                            match throw_index {
+                                RSZValue::Int32(int) => {
+                                    resolve_box(
+                                        fchar,
+                                        int.clone(),
+                                        DataId::ThrowHurtBox,
+                                        &mut throw_boxes,
+                                        &mut frame_boxes.missing_boxes,
+                                    );
+                                }
+                                _ => (),
+                            }
+                        }
+
+                        let mut condition = 0u8;
+                        match &data.fields[0].value {
+                            RSZValue::UInt8(ubyte) => condition = ubyte.clone(),
+                            _ => (),
+                        }
+                        let mut collision_type = 0u8;
+                        match &data.fields[1].value {
+                            RSZValue::Int8(byte) => collision_type = byte.clone() as u8,
+                            _ => (),
+                        }
+                        let mut immune = 0u8;
+                        match &data.fields[2].value {
+                            RSZValue::UInt8(ubyte) => immune = ubyte.clone(),
+                            _ => (),
+                        }
+                        let mut extend = 0u8;
+                        match &data.fields[3].value {
+                            RSZValue::UInt8(ubyte) => extend = ubyte.clone(),
+                            _ => (),
+                        }
+                        let mut level = 0u8;
+                        match &data.fields[4].value {
+                            RSZValue::UInt8(ubyte) => level = ubyte.clone(),
+                            _ => (),
+                        }
+                        let mut type_flag = 0u32;
+                        match &data.fields[5].value {
+                            RSZValue::UInt32(uint) => type_flag = uint.clone(),
+                            _ => (),
+                        }
+
+                        let damage_collision = DamageCollisionKey {
+                            condition,
+                            collision_type,
+                            immune,
+                            extend,
+                            level,
+                            type_flag,
+                            head_boxes,
+                            body_boxes,
+                            leg_boxes,
+                            throw_boxes,
+                            object_id,
+                        };
+                        frame_boxes.damage_collision_keys.push(damage_collision)
+                    }
+                    "CharacterAsset.AttackCollisionKey" => {
+                        let mut boxes: Vec<CollisionBox> = vec![];
+
+                        let mut condition = 0u8;
+                        match &data.fields[0].value {
+                            RSZValue::UInt8(ubyte) => condition = ubyte.clone(),
+                            _ => (),
+                        }
+                        let mut collision_type = 0u8;
+                        match &data.fields[1].value {
+                            RSZValue::UInt8(ubyte) => collision_type = ubyte.clone(),
+                            _ => (),
+                        }
+                        let mut hit_id = 0i8;
+                        match &data.fields[2].value {
+                            RSZValue::Int8(byte) => hit_id = byte.clone(),
+                            _ => (),
+                        }
+                        let mut guard_bit = 0u8;
+                        match &data.fields[3].value {
+                            RSZValue::UInt8(ubyte) => guard_bit = ubyte.clone(),
+                            _ => (),
+                        }
+                        let mut kind_flag = 0u32;
+                        match &data.fields[4].value {
+                            RSZValue::UInt32(uint) => kind_flag = uint.clone(),
+                            _ => (),
+                        }
+                        let hit_offset = int2_to_array(&data.fields[6].value);
+
+                        let mut hit_data: Option<HitData> = None;
+                        match &data.fields[8].value {
+                            RSZValue::Int32(int) => hit_data = get_hit_data(fchar, int.clone()),
+                            _ => (),
+                        }
+
+                        let mut box_list: &Vec<RSZValue> = &vec![];
+                        match &data.fields[11].value {
+                            RSZValue::List(list) => box_list = list,
+                            _ => (),
+                        }
+                        for index in box_list {
+                            match index {
+                                RSZValue::Int32(int) => {
+                                    if collision_type == COLLISION_TYPE_PROXIMITY {
+                                        resolve_box(
+                                            fchar,
+                                            int.clone(),
+                                            DataId::ProximityBox,
+                                            &mut boxes,
+                                            &mut frame_boxes.missing_boxes,
+                                        );
+                                    } else {
+                                        resolve_box(
+                                            fchar,
+                                            int.clone(),
+                                            DataId::StrikeBox,
+                                            &mut boxes,
+                                            &mut frame_boxes.missing_boxes,
+                                        );
+                                    }
+                                }
+                                _ => (),
+                            }
+                        }
+
+                        let attack_collision = AttackCollisionKey {
+                            condition,
+                            collision_type,
+                            hit_id,
+                            guard_bit,
+                            kind_flag,
+                            hit_offset,
+                            boxes,
+                            object_id,
+                            hit_data,
+                        };
+                        frame_boxes.attack_collision_keys.push(attack_collision)
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+    frame_boxes
+}
+
+/// Scans every key in the action once to find out what exists on each frame.
+pub fn get_timeline(fchar: &CharacterAsset, action_index: usize) -> Vec<TimelineCell> {
+    let action = &fchar.action_list[action_index];
+    let mut timeline: Vec<TimelineCell> =
+        vec![Default::default(); action.info.action_data.frames.max(0) as usize];
+    for object in &action.objects {
+        for (index, object_index) in object.action.object_table.iter().enumerate() {
+            let data = &object.action.data[object_index.clone() as usize - 1];
+            let key_data = &object.info.object_data.key_data[index];
+            let key_frames = key_data.key_start_frame.max(0)..key_data.key_end_frame;
+            match data.name.as_str() {
+                "CharacterAsset.AttackCollisionKey" => {
+                    match &data.fields[1].value {
+                        RSZValue::UInt8(collision_type) => {
+                            if *collision_type == COLLISION_TYPE_PROXIMITY {
+                                continue;
+                            }
+                        }
+                        _ => (),
+                    }
+                    let mut kind_flag = 0u32;
+                    match &data.fields[4].value {
+                        RSZValue::UInt32(uint) => kind_flag = uint.clone(),
+                        _ => (),
+                    }
+                    for frame in key_frames {
+                        if let Some(cell) = timeline.get_mut(frame as usize) {
+                            cell.attack = true;
+                            cell.attack_kind_flag |= kind_flag;
+                        }
+                    }
+                }
+                "CharacterAsset.DamageCollisionKey" => {
+                    let mut immune = 0u8;
+                    match &data.fields[2].value {
+                        RSZValue::UInt8(ubyte) => immune = ubyte.clone(),
+                        _ => (),
+                    }
+                    for frame in key_frames {
+                        if let Some(cell) = timeline.get_mut(frame as usize) {
+                            cell.hurtbox = true;
+                            cell.invulnerable |= immune != 0;
+                            cell.immune |= immune;
+                        }
+                    }
+                }
+                "CharacterAsset.PushCollisionKey" => {
+                    if key_data.key_start_frame > 0 {
+                        if let Some(cell) = timeline.get_mut(key_data.key_start_frame as usize) {
+                            cell.pushbox_change = true;
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+    timeline
+}
+
+/// Whether any key of the action is an attack key other than a proximity guard box.
+pub fn has_attack_boxes(fchar: &CharacterAsset, action_index: usize) -> bool {
+    let action = &fchar.action_list[action_index];
+    for object in &action.objects {
+        for object_index in &object.action.object_table {
+            let data = &object.action.data[object_index.clone() as usize - 1];
+            if data.name != "CharacterAsset.AttackCollisionKey" {
+                continue;
+            }
+            match &data.fields[1].value {
+                RSZValue::UInt8(collision_type) => {
+                    if *collision_type != COLLISION_TYPE_PROXIMITY {
+                        return true;
+                    }
+                }
+                _ => return true,
+            }
+        }
+    }
+    false
+}
+
+/// The character-wide values from the PersonalData instance.
+#[derive(Default, Clone)]
+pub struct PersonalData {
+    pub vitality: i32,
+    pub gauge: i32,
+    pub weight: u8,
+    pub multi_jump_count: u8,
+}
+
+fn field_to_i32(value: &RSZValue) -> Option<i32> {
+    match value {
+        RSZValue::Int8(value) => Some(value.clone() as i32),
+        RSZValue::UInt8(value) => Some(value.clone() as i32),
+        RSZValue::Int16(value) => Some(value.clone() as i32),
+        RSZValue::UInt16(value) => Some(value.clone() as i32),
+        RSZValue::Int32(value) => Some(value.clone()),
+        _ => None,
+    }
+}
+
+/// Looks through the data lists for the character's PersonalData. Common and effect files
+/// don't have one.
+pub fn get_personal_data(fchar: &CharacterAsset) -> Option<PersonalData> {
+    let data = fchar.data_list_table.iter().find_map(|data_list| {
+        data_list
+            .data_rsz
+            .data
+            .iter()
+            .find(|data| data.name == "CharacterAsset.PersonalData")
+    })?;
+    let field = |index: usize| match data.fields.get(index) {
+        Some(field) => field_to_i32(&field.value).unwrap_or_default(),
+        None => 0,
+    };
+    Some(PersonalData {
+        vitality: field(0),
+        gauge: field(1),
+        weight: field(15) as u8,
+        multi_jump_count: field(18) as u8,
+    })
+}
+
+/// Active windows as displayed frames, from a timeline built by [`get_timeline`].
+pub fn get_active_windows(timeline: &[TimelineCell]) -> Vec<(usize, usize)> {
+    let mut active_windows: Vec<(usize, usize)> = vec![];
+    // Windows are stored as displayed frames, so key frame 0 is frame 1.
+    let mut window_start: usize = 0;
+    for (frame, cell) in timeline.iter().enumerate() {
+        if cell.attack && window_start == 0 {
+            window_start = frame + 1;
+        } else if !cell.attack && window_start != 0 {
+            active_windows.push((window_start, frame));
+            window_start = 0;
+        }
+    }
+    if window_start != 0 {
+        active_windows.push((window_start, timeline.len()));
+    }
+    active_windows
+}
+
+/// One hit of an action: a run of consecutive frames with attack boxes of the same hit ID.
+#[derive(Clone)]
+pub struct HitWindow {
+    pub hit_id: i8,
+    pub object_id: usize,
+    /// Displayed frames, like active windows.
+    pub first_frame: usize,
+    pub last_frame: usize,
+    pub guard_requirement: GuardRequirement,
+}
+
+/// Splits the attack boxes of an action, from [`get_frames`], into hits by hit ID, in the order
+/// they come out. A hit ID that stops and starts again counts as separate hits. Proximity guard
+/// boxes are left out.
+pub fn get_hit_windows(frames: &[FrameBoxes]) -> Vec<HitWindow> {
+    let mut hit_windows: Vec<HitWindow> = vec![];
+    for (frame, frame_boxes) in frames.iter().enumerate() {
+        for attack_collision_key in &frame_boxes.attack_collision_keys {
+            if attack_collision_key.is_proximity() || attack_collision_key.boxes.is_empty() {
+                continue;
+            }
+            let open_window = hit_windows.iter_mut().find(|hit_window| {
+                hit_window.hit_id == attack_collision_key.hit_id
+                    && hit_window.object_id == attack_collision_key.object_id
+                    && hit_window.last_frame >= frame
+            });
+            match open_window {
+                Some(hit_window) => hit_window.last_frame = frame + 1,
+                None => hit_windows.push(HitWindow {
+                    hit_id: attack_collision_key.hit_id,
+                    object_id: attack_collision_key.object_id,
+                    first_frame: frame + 1,
+                    last_frame: frame + 1,
+                    guard_requirement: attack_collision_key.guard_requirement(),
+                }),
+            }
+        }
+    }
+    hit_windows
+}
+
+/// The fields `get_boxes` reads by index, by class, with the schema names they should have. A
+/// game update that adds or moves fields shifts these.
+type BoxKeyFields = [(&'static str, &'static [(usize, &'static str)]); 3];
+
+const BOX_KEY_FIELDS: BoxKeyFields = [
+    (
+        "CharacterAsset.PushCollisionKey",
+        &[(0, "Condition"), (1, "Attribute"), (2, "BoxNo")],
+    ),
+    (
+        "CharacterAsset.DamageCollisionKey",
+        &[
+            (0, "Condition"),
+            (1, "CollisionType"),
+            (2, "Immune"),
+            (3, "Extend"),
+            (4, "Level"),
+            (5, "TypeFlag"),
+            (9, "HeadList"),
+            (10, "BodyList"),
+            (11, "LegList"),
+            (12, "ThrowList"),
+        ],
+    ),
+    (
+        "CharacterAsset.AttackCollisionKey",
+        &[
+            (0, "Condition"),
+            (1, "CollisionType"),
+            (2, "HitID"),
+            (3, "GuardBit"),
+            (4, "KindFlag"),
+            (6, "HitOffset"),
+            (8, "AttackDataListIndex"),
+            (11, "BoxList"),
+        ],
+    ),
+];
+
+#[derive(Deserialize)]
+struct SchemaField {
+    name: String,
+    #[serde(rename = "type")]
+    field_type: String,
+    array: bool,
+}
+
+#[derive(Deserialize)]
+struct SchemaClass {
+    name: String,
+    fields: Vec<SchemaField>,
+}
+
+/// The name and type of every field of a class in `BOX_KEY_FIELDS`, as the embedded schema
+/// lists them. Array fields have the type "List", since that's what they're parsed into.
+fn schema_fields(class_name: &str) -> Option<&'static [(String, String)]> {
+    static FIELDS: OnceLock<HashMap<String, Vec<(String, String)>>> = OnceLock::new();
+    FIELDS
+        .get_or_init(|| {
+            let classes: HashMap<String, SchemaClass> =
+                serde_json::from_slice(&schema_json()).unwrap_or_default();
+            classes
+                .into_values()
+                .filter(|class| BOX_KEY_FIELDS.iter().any(|(name, _)| *name == class.name))
+                .map(|class| {
+                    let fields = class
+                        .fields
+                        .into_iter()
+                        .map(|field| {
+                            let field_type = if field.array {
+                                "List".to_string()
+                            } else {
+                                field.field_type
+                            };
+                            (field.name, field_type)
+                        })
+                        .collect();
+                    (class.name, fields)
+                })
+                .collect()
+        })
+        .get(class_name)
+        .map(|fields| fields.as_slice())
+}
+
+/// The schema's name for the type of a parsed value, for the types `BOX_KEY_FIELDS` uses.
+fn value_type_name(value: &RSZValue) -> &'static str {
+    match value {
+        RSZValue::Int8(_) => "S8",
+        RSZValue::UInt8(_) => "U8",
+        RSZValue::UInt16(_) => "U16",
+        RSZValue::Int32(_) => "S32",
+        RSZValue::UInt32(_) => "U32",
+        RSZValue::Int2(_) => "Int2",
+        RSZValue::List(_) => "List",
+        _ => "another type",
+    }
+}
+
+/// Checks that the fields `get_boxes` reads are where it expects them in the schema, and that
+/// the first key of each collision type in the file has the schema's type in each of them.
+/// Describes every mismatch; nothing is returned when the layout lines up.
+pub fn check_box_key_fields(fchar: &CharacterAsset) -> Vec<String> {
+    let mut warnings: Vec<String> = vec![];
+    for (class_name, expected_fields) in BOX_KEY_FIELDS {
+        let schema = match schema_fields(class_name) {
+            Some(schema) => schema,
+            None => {
+                warnings.push(format!("{} isn't in the schema", class_name));
+                continue;
+            }
+        };
+        let data = fchar.action_list.iter().find_map(|action| {
+            action.objects.iter().find_map(|object| {
+                object
+                    .action
+                    .data
+                    .iter()
+                    .find(|data| data.name == class_name)
+            })
+        });
+        for (index, field_name) in expected_fields.iter() {
+            let type_name = match schema.get(*index) {
+                Some((schema_name, type_name)) if schema_name == field_name => type_name,
+                Some((schema_name, _)) => {
+                    warnings.push(format!(
+                        "{} field {} is {} in the schema, expected {}",
+                        class_name, index, schema_name, field_name
+                    ));
+                    continue;
+                }
+                None => {
+                    warnings.push(format!(
+                        "{} has {} fields in the schema, expected {} at {}",
+                        class_name,
+                        schema.len(),
+                        field_name,
+                        index
+                    ));
+                    continue;
+                }
+            };
+            let data = match data {
+                Some(data) => data,
+                None => continue,
+            };
+            match data.fields.get(*index) {
+                Some(field) => {
+                    let found = value_type_name(&field.value);
+                    if found != type_name {
+                        warnings.push(format!(
+                            "{} field {} ({}) is {}, expected {}",
+                            class_name, index, field_name, found, type_name
+                        ));
+                    }
+                }
+                None => warnings.push(format!(
+                    "{} has {} fields, expected {} at {}",
+                    class_name,
+                    data.fields.len(),
+                    field_name,
+                    index
+                )),
+            }
+        }
+    }
+    warnings
+}
+
+/// Resolves the boxes and cancels of every frame of an action, indexed by key frame.
+pub fn get_frames(fchar: &CharacterAsset, action_index: usize) -> Vec<FrameBoxes> {
+    let frames = fchar.action_list[action_index]
+        .info
+        .action_data
+        .frames
+        .max(0) as usize;
+    let mut frame_cache: Vec<FrameBoxes> = vec![];
+    for frame in 0..frames {
+        let mut frame_boxes = get_boxes(fchar, action_index, frame as i32);
+        frame_boxes.triggers = get_trigger_keys(fchar, action_index, frame as i32);
+        frame_cache.push(frame_boxes);
+    }
+    frame_cache
+}
+
+/// One key of an action with every field as the file stores it, decoded or not.
+pub struct RawKey {
+    pub object_id: usize,
+    pub name: String,
+    pub start_frame: i32,
+    pub end_frame: i32,
+    /// Field name and value, in file order.
+    pub fields: Vec<(String, String)>,
+}
+
+/// Formats any parsed value, including the ones nothing else reads.
+pub fn rsz_value_to_string(value: &RSZValue) -> String {
+    match value {
+        RSZValue::Bool(bool) => bool.to_string(),
+        RSZValue::Int8(value) => value.to_string(),
+        RSZValue::UInt8(value) => value.to_string(),
+        RSZValue::Int16(value) => value.to_string(),
+        RSZValue::UInt16(value) => format!("{} ({:#06x})", value, value),
+        RSZValue::Int32(value) => value.to_string(),
+        RSZValue::UInt32(value) => format!("{} ({:#010x})", value, value),
+        RSZValue::Int64(value) => value.to_string(),
+        RSZValue::UInt64(value) => format!("{} ({:#018x})", value, value),
+        RSZValue::Float(value) => value.to_string(),
+        RSZValue::Double(value) => value.to_string(),
+        RSZValue::String(string) => format!("{:?}", string),
+        RSZValue::Float3(float3) => format!("({}, {}, {})", float3.x, float3.y, float3.z),
+        RSZValue::Int2(int2) => format!("({}, {})", int2.x, int2.y),
+        RSZValue::List(list) => format!(
+            "[{}]",
+            list.iter()
+                .map(rsz_value_to_string)
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        RSZValue::Object(object) => format!(
+            "{{{}}}",
+            object
+                .fields
+                .iter()
+                .map(|field| format!("{}: {}", field.name, rsz_value_to_string(&field.value)))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        _ => "(unknown value)".to_string(),
+    }
+}
+
+/// Every key of every object active on a key frame of the action.
+pub fn get_raw_keys(fchar: &CharacterAsset, action_index: usize, frame: i32) -> Vec<RawKey> {
+    let mut raw_keys: Vec<RawKey> = vec![];
+    let action = &fchar.action_list[action_index];
+    for (object_id, object) in action.objects.iter().enumerate() {
+        for (index, object_index) in object.action.object_table.iter().enumerate() {
+            let key_data = &object.info.object_data.key_data[index];
+            if key_active(key_data.key_start_frame, key_data.key_end_frame, frame) {
+                let data = &object.action.data[object_index.clone() as usize - 1];
+                raw_keys.push(RawKey {
+                    object_id,
+                    name: data.name.clone(),
+                    start_frame: key_data.key_start_frame,
+                    end_frame: key_data.key_end_frame,
+                    fields: data
+                        .fields
+                        .iter()
+                        .map(|field| (field.name.clone(), rsz_value_to_string(&field.value)))
+                        .collect(),
+                });
+            }
+        }
+    }
+    raw_keys
+}
+
+/// The embedded RSZ schema, decompressed.
+fn schema_json() -> Vec<u8> {
+    include_bytes_zstd!("assets/rszsf6.json", 9)
+}
+
+/// Parses the embedded RSZ schema the first time a file is opened. The parser keeps the schema
+/// around, so every later call just returns the first result.
+pub fn load_schema() -> Result<(), String> {
+    static SCHEMA: OnceLock<Result<(), String>> = OnceLock::new();
+    SCHEMA
+        .get_or_init(|| {
+            parse_json(schema_json())
+                .map(|_| ())
+                .map_err(|err| format!("the embedded RSZ schema is invalid ({:?})", err))
+        })
+        .clone()
+}
+
+/// Describes where parsing stopped. The parser's errors hold the rest of the input, which is
+/// far too long to show as is.
+fn parse_error_to_string(buffer: &[u8], err: nom::Err<nom::error::Error<&[u8]>>) -> String {
+    match err {
+        nom::Err::Incomplete(_) => "the file ends early".to_string(),
+        nom::Err::Error(error) | nom::Err::Failure(error) => format!(
+            "{} failed at byte {:#x}",
+            error.code.description(),
+            buffer.len() - error.input.len()
+        ),
+    }
+}
+
+/// Parses an fchar file, loading the schema first if no file has been opened yet.
+pub fn open(buffer: &[u8]) -> Result<CharacterAsset, String> {
+    load_schema()?;
+    match parse_fchar(buffer) {
+        Ok((_, fchar)) => Ok(fchar),
+        Err(err) => Err(parse_error_to_string(buffer, err)),
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum Character {
+    #[default]
+    Common,
+    Ryu,
+    Luke,
+    Kimberly,
+    ChunLi,
+    Manon,
+    Zangief,
+    JP,
+    Dhalsim,
+    Cammy,
+    Ken,
+    DeeJay,
+    Lily,
+    Blanka,
+    Juri,
+    Marisa,
+    Guile,
+    EHonda,
+    Jamie,
+    Unknown,
+}
+
+/// A bundled character: its display name and the number its files are named by, e.g. 1 for
+/// 001.fchar.17. The numbers skip characters that aren't in the game.
+pub struct CharacterInfo {
+    pub character: Character,
+    pub name: &'static str,
+    pub file_id: u32,
+}
+
+/// Every bundled character, in menu order.
+pub const CHARACTERS: [CharacterInfo; 19] = [
+    CharacterInfo {
+        character: Character::Common,
+        name: "Common",
+        file_id: 0,
+    },
+    CharacterInfo {
+        character: Character::Ryu,
+        name: "Ryu",
+        file_id: 1,
+    },
+    CharacterInfo {
+        character: Character::Luke,
+        name: "Luke",
+        file_id: 2,
+    },
+    CharacterInfo {
+        character: Character::Kimberly,
+        name: "Kimberly",
+        file_id: 3,
+    },
+    CharacterInfo {
+        character: Character::ChunLi,
+        name: "Chun-Li",
+        file_id: 4,
+    },
+    CharacterInfo {
+        character: Character::Manon,
+        name: "Manon",
+        file_id: 5,
+    },
+    CharacterInfo {
+        character: Character::Zangief,
+        name: "Zangief",
+        file_id: 6,
+    },
+    CharacterInfo {
+        character: Character::JP,
+        name: "JP",
+        file_id: 7,
+    },
+    CharacterInfo {
+        character: Character::Dhalsim,
+        name: "Dhalsim",
+        file_id: 8,
+    },
+    CharacterInfo {
+        character: Character::Cammy,
+        name: "Cammy",
+        file_id: 9,
+    },
+    CharacterInfo {
+        character: Character::Ken,
+        name: "Ken",
+        file_id: 10,
+    },
+    CharacterInfo {
+        character: Character::DeeJay,
+        name: "Dee Jay",
+        file_id: 11,
+    },
+    CharacterInfo {
+        character: Character::Lily,
+        name: "Lily",
+        file_id: 12,
+    },
+    CharacterInfo {
+        character: Character::Blanka,
+        name: "Blanka",
+        file_id: 15,
+    },
+    CharacterInfo {
+        character: Character::Juri,
+        name: "Juri",
+        file_id: 16,
+    },
+    CharacterInfo {
+        character: Character::Marisa,
+        name: "Marisa",
+        file_id: 17,
+    },
+    CharacterInfo {
+        character: Character::Guile,
+        name: "Guile",
+        file_id: 18,
+    },
+    CharacterInfo {
+        character: Character::EHonda,
+        name: "E. Honda",
+        file_id: 20,
+    },
+    CharacterInfo {
+        character: Character::Jamie,
+        name: "Jamie",
+        file_id: 21,
+    },
+];
+
+impl Character {
+    /// Recognises characters by the number at the start of a file name like "001.fchar.17".
+    pub fn from_file_name(file_name: &str) -> Option<&'static CharacterInfo> {
+        let digits: String = file_name
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        let file_id: u32 = digits.parse().ok()?;
+        CHARACTERS.iter().find(|info| info.file_id == file_id)
+    }
+}
+
+/// The name of an action ID in one of the generated action enums, whose default variant is
+/// the invalid ID.
+fn enum_name<T>(action_id: i32) -> Option<String>
+where
+    T: num::FromPrimitive + Default + PartialEq + ToString,
+{
+    let action_name: T = num::FromPrimitive::from_i32(action_id).unwrap_or_default();
+    if action_name == T::default() {
+        None
+    } else {
+        Some(action_name.to_string())
+    }
+}
+
+/// The name of `action_id` in the character's action list, or the ID itself if it has none.
+pub fn action_name(character: Character, action_id: i32) -> String {
+    let action_name = match character {
+        Character::Common => enum_name::<action_names::CommonActions>(action_id),
+        Character::Ryu => enum_name::<action_names::RyuActions>(action_id),
+        Character::Luke => enum_name::<action_names::LukeActions>(action_id),
+        Character::Kimberly => enum_name::<action_names::KimberlyActions>(action_id),
+        Character::ChunLi => enum_name::<action_names::ChunLiActions>(action_id),
+        Character::Manon => enum_name::<action_names::ManonActions>(action_id),
+        Character::Zangief => enum_name::<action_names::ZangiefActions>(action_id),
+        Character::JP => enum_name::<action_names::JPActions>(action_id),
+        Character::Dhalsim => enum_name::<action_names::DhalsimActions>(action_id),
+        Character::Cammy => enum_name::<action_names::CammyActions>(action_id),
+        Character::Ken => enum_name::<action_names::KenActions>(action_id),
+        Character::DeeJay => enum_name::<action_names::DeeJayActions>(action_id),
+        Character::Lily => enum_name::<action_names::LilyActions>(action_id),
+        Character::Blanka => enum_name::<action_names::BlankaActions>(action_id),
+        Character::Juri => enum_name::<action_names::JuriActions>(action_id),
+        Character::Marisa => enum_name::<action_names::MarisaActions>(action_id),
+        Character::Guile => enum_name::<action_names::GuileActions>(action_id),
+        Character::EHonda => enum_name::<action_names::EHondaActions>(action_id),
+        Character::Jamie => enum_name::<action_names::JamieActions>(action_id),
+        Character::Unknown => None,
+    };
+    action_name.unwrap_or(format!("{}", action_id))
+}
+
+/// Startup counts the first active frame itself, as the game's frame data does.
+pub fn startup(action_info: &ActionInfo) -> Option<i32> {
+    if action_info.first_active_frame == -1 {
+        return None;
+    }
+    Some(action_info.first_active_frame + 1)
+}
+
+/// Frames between the last active frame and the first actionable frame.
+pub fn recovery(action_info: &ActionInfo, active_windows: &[(usize, usize)]) -> Option<i32> {
+    active_windows
+        .last()
+        .map(|(_, last_active_frame)| action_info.end_frame - *last_active_frame as i32)
+}
+
+/// How many boxes of each kind are out on one frame.
+#[derive(Serialize)]
+pub struct FrameDump {
+    pub frame: usize,
+    pub pushboxes: usize,
+    pub hurtboxes: usize,
+    pub throw_hurtboxes: usize,
+    pub hitboxes: usize,
+    pub proximity_boxes: usize,
+}
+
+/// Frame data of one action and its box counts on every frame.
+#[derive(Serialize)]
+pub struct ActionDump {
+    pub index: usize,
+    pub action_id: i32,
+    pub name: String,
+    pub startup: Option<i32>,
+    pub active: Vec<(usize, usize)>,
+    pub recovery: Option<i32>,
+    pub frames: Vec<FrameDump>,
+}
+
+/// Summarises the frame data and per-frame box counts of the action at `action_index`.
+pub fn dump_action(
+    fchar: &CharacterAsset,
+    character: Character,
+    action_index: usize,
+) -> ActionDump {
+    let action_id = fchar.action_list[action_index]
+        .info
+        .action_data
+        .action_id
+        .clone();
+    let action_info = get_action_info(fchar, action_index);
+    let active = get_active_windows(&get_timeline(fchar, action_index));
+    let mut frame_dumps: Vec<FrameDump> = vec![];
+    for (frame_index, frame_boxes) in get_frames(fchar, action_index).iter().enumerate() {
+        let mut frame_dump = FrameDump {
+            frame: frame_index + 1,
+            pushboxes: frame_boxes.push_collision_keys.len(),
+            hurtboxes: 0,
+            throw_hurtboxes: 0,
+            hitboxes: 0,
+            proximity_boxes: 0,
+        };
+        for damage_collision_key in &frame_boxes.damage_collision_keys {
+            frame_dump.hurtboxes += damage_collision_key.hurtboxes().count();
+            frame_dump.throw_hurtboxes += damage_collision_key.throw_boxes.len();
+        }
+        for attack_collision_key in &frame_boxes.attack_collision_keys {
+            if attack_collision_key.is_proximity() {
+                frame_dump.proximity_boxes += attack_collision_key.boxes.len();
+            } else {
+                frame_dump.hitboxes += attack_collision_key.boxes.len();
+            }
+        }
+        frame_dumps.push(frame_dump);
+    }
+    ActionDump {
+        index: action_index,
+        action_id,
+        name: action_name(character, action_id),
+        startup: startup(&action_info),
+        recovery: recovery(&action_info, &active),
+        active,
+        frames: frame_dumps,
+    }
+}
+
+/// Parses `path` and returns the frame data of every action (or only `action`) as JSON.
+pub fn dump(path: &str, action: Option<usize>) -> Result<String, String> {
+    let buffer = std::fs::read(path).map_err(|err| format!("Failed to read {}: {}", path, err))?;
+    let fchar = open(&buffer).map_err(|err| format!("Failed to parse {}: {}", path, err))?;
+    let file_name = std::path::Path::new(path)
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let character = match Character::from_file_name(&file_name) {
+        Some(info) => info.character,
+        None => Character::Unknown,
+    };
+    let dumps: Vec<ActionDump> = match action {
+        Some(action) if action < fchar.action_list.len() => {
+            vec![dump_action(&fchar, character, action)]
+        }
+        Some(action) => return Err(format!("No action #{} in {}", action, path)),
+        None => (0..fchar.action_list.len())
+            .map(|index| dump_action(&fchar, character, index))
+            .collect(),
+    };
+    serde_json::to_string_pretty(&dumps).map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sf6_rsz_parser::rsz::{Int2, RSZData, RSZField};
+
+    #[test]
+    fn key_on_the_first_three_key_frames_shows_on_frames_1_to_3() {
+        let shown: Vec<usize> = (0..=5)
+            .filter(|displayed_frame| key_active(0, 3, key_frame(*displayed_frame)))
+            .collect();
+        assert_eq!(shown, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn trigger_ids_counts_64_triggers_per_word() {
+        assert!(trigger_ids(&[]).is_empty());
+        assert!(trigger_ids(&[0, 0]).is_empty());
+        assert_eq!(trigger_ids(&[0b1011]), vec![0, 1, 3]);
+        assert_eq!(trigger_ids(&[1 << 63, 1 | 1 << 5]), vec![63, 64, 69]);
+    }
+
+    #[test]
+    fn box_field_to_f32_reads_mixed_field_types() {
+        let fields = [
+            RSZValue::Int16(-20),
+            RSZValue::Int32(40_000),
+            RSZValue::Float(12.5),
+            RSZValue::Int16(30),
+        ];
+        let values: Vec<f32> = fields.iter().map(box_field_to_f32).collect();
+        assert_eq!(values, vec![-20.0, 40_000.0, 12.5, 30.0]);
+        assert_eq!(box_field_to_f32(&RSZValue::UInt8(7)), 0.0);
+    }
+
+    #[test]
+    fn hit_offset_is_read_from_the_int2_field() {
+        let attack_fields = schema_fields("CharacterAsset.AttackCollisionKey").unwrap();
+        assert_eq!(
+            attack_fields[6],
+            ("HitOffset".to_string(), "Int2".to_string())
+        );
+        let hit_offset = RSZValue::Int2(Int2 { x: 12, y: -8 });
+        assert_eq!(int2_to_array(&hit_offset), [12, -8]);
+        assert_eq!(int2_to_array(&RSZValue::UInt32(0x10)), [0, 0]);
+    }
+
+    #[test]
+    fn ryu_5lp_has_a_hit_offset() {
+        let buffer =
+            std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/001.fchar.17")).unwrap();
+        let fchar = open(&buffer).unwrap();
+        // Ryu_5lp. Its strike key's HitOffset is (94, 142) in the bundled file.
+        let action_index = fchar
+            .action_list
+            .iter()
+            .position(|action| action.info.action_data.action_id == 600)
+            .unwrap();
+        let frames = fchar.action_list[action_index].info.action_data.frames;
+        let hit_offsets: Vec<[i32; 2]> = (0..frames)
+            .flat_map(|frame| get_boxes(&fchar, action_index, frame).attack_collision_keys)
+            .map(|attack_collision_key| attack_collision_key.hit_offset)
+            .collect();
+        assert!(hit_offsets.iter().any(|hit_offset| *hit_offset != [0, 0]));
+    }
+
+    fn named_object(fields: Vec<(&str, RSZValue)>) -> RSZValue {
+        RSZValue::Object(RSZData {
+            name: "".to_string(),
+            fields: fields
+                .into_iter()
+                .map(|(name, value)| RSZField {
+                    name: name.to_string(),
+                    value,
+                })
+                .collect(),
+        })
+    }
+
+    fn object(values: Vec<RSZValue>) -> RSZValue {
+        named_object(values.into_iter().map(|value| ("", value)).collect())
+    }
+
+    #[test]
+    fn box_coordinates_reads_nested_records_depth_first() {
+        let flat = [
+            RSZValue::Int16(1),
+            RSZValue::Int16(2),
+            RSZValue::Int16(3),
+            RSZValue::Int16(4),
+        ];
+        let flat: Vec<&RSZValue> = flat.iter().collect();
+        assert_eq!(box_coordinates(&flat), vec![1.0, 2.0, 3.0, 4.0]);
+
+        let wrapped = object(vec![
+            RSZValue::Int16(-10),
+            RSZValue::Int32(20),
+            RSZValue::Float(30.5),
+            RSZValue::Int16(40),
+        ]);
+        assert_eq!(box_coordinates(&[&wrapped]), vec![-10.0, 20.0, 30.5, 40.0]);
+
+        let position = object(vec![RSZValue::Int16(-10), RSZValue::Int16(20)]);
+        let size = object(vec![RSZValue::Int16(30), RSZValue::Int16(40)]);
+        let list = RSZValue::List(vec![RSZValue::Int16(99)]);
+        assert_eq!(
+            box_coordinates(&[&position, &list, &size]),
+            vec![-10.0, 20.0, 30.0, 40.0]
+        );
+    }
+
+    #[test]
+    fn rsz_value_to_string_formats_nested_objects() {
+        let position = named_object(vec![
+            ("X", RSZValue::Int16(-10)),
+            ("Y", RSZValue::Float(2.5)),
+        ]);
+        let record = named_object(vec![
+            ("Position", position),
+            ("Flags", RSZValue::List(vec![RSZValue::Bool(true)])),
+        ]);
+        assert_eq!(
+            rsz_value_to_string(&record),
+            "{Position: {X: -10, Y: 2.5}, Flags: [true]}"
+        );
+    }
+
+    #[test]
+    fn box_key_fields_match_the_schema() {
+        let damage_fields = schema_fields("CharacterAsset.DamageCollisionKey").unwrap();
+        assert_eq!(
+            damage_fields[1],
+            ("CollisionType".to_string(), "S8".to_string())
+        );
+        assert_eq!(
+            damage_fields[9],
+            ("HeadList".to_string(), "List".to_string())
+        );
+        for (class_name, expected_fields) in BOX_KEY_FIELDS {
+            let schema = schema_fields(class_name).unwrap();
+            for (index, field_name) in expected_fields.iter() {
+                assert_eq!(schema[*index].0, *field_name);
+            }
+        }
+    }
+
+    #[test]
+    fn flags_to_string_names_known_bits_and_shows_the_rest_as_hex() {
+        let names = [
+            (0b11, "both"),
+            (1 << 0, "first"),
+            (1 << 1, "second"),
+            (1 << 2, "third"),
+        ];
+        assert_eq!(flags_to_string(0, &names, "+"), None);
+        assert_eq!(
+            flags_to_string(0b100, &names, "+"),
+            Some("third".to_string())
+        );
+        assert_eq!(
+            flags_to_string(0b001, &names, "+"),
+            Some("first".to_string())
+        );
+        assert_eq!(
+            flags_to_string(0b111, &names, "+"),
+            Some("both+third".to_string())
+        );
+        assert_eq!(
+            flags_to_string(0b1_0010, &names, " | "),
+            Some("second | 0x10".to_string())
+        );
+        assert_eq!(flags_to_string(0x80, &[], "+"), Some("0x80".to_string()));
+    }
+
+    #[test]
+    fn actions_without_frames_have_no_frame_data() {
+        let buffer =
+            std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/001.fchar.17")).unwrap();
+        let mut fchar = open(&buffer).unwrap();
+        for frames in [0, -1] {
+            fchar.action_list[0].info.action_data.frames = frames;
+            assert!(get_timeline(&fchar, 0).is_empty());
+            assert!(get_frames(&fchar, 0).is_empty());
+            let dump = dump_action(&fchar, Character::Common, 0);
+            assert!(dump.frames.is_empty());
+            assert!(dump.active.is_empty());
+        }
+    }
+}
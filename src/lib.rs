@@ -1,4 +1,8 @@
 #![warn(clippy::all, rust_2018_idioms)]
 
+pub mod extract;
+
+#[cfg(feature = "gui")]
 mod app;
+#[cfg(feature = "gui")]
 pub use app::SF6Simulator;
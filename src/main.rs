@@ -1,7 +1,5 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
-mod app;
-
 use eframe::emath::Vec2;
 
 // When compiling natively:
@@ -17,7 +15,7 @@ fn main() {
     eframe::run_native(
         "Street Fighter 6 Simulator",
         options,
-        Box::new(|cc| Box::new(app::SF6Simulator::new(cc))),
+        Box::new(|cc| Box::new(sf6_sim::SF6Simulator::new(cc))),
     )
     .expect("Failed to start GUI!");
 }
@@ -35,7 +33,7 @@ fn main() {
             .start(
                 "sf6sim",
                 web_options,
-                Box::new(|cc| Box::new(app::SF6Simulator::new(cc))),
+                Box::new(|cc| Box::new(sf6_sim::SF6Simulator::new(cc))),
             )
             .await
             .expect("failed to start eframe");